@@ -0,0 +1,73 @@
+//! Generates `tables.rs`, containing the string representations of every `Perm` and `Mode`.
+
+use std::{env, fmt::Write as _, fs, path::Path};
+
+fn num(bits: u16) -> String {
+    (bits & 0o7).to_string()
+}
+
+fn sym(bits: u16) -> String {
+    let r = if bits & 0o4 != 0 { "r" } else { "" };
+    let w = if bits & 0o2 != 0 { "w" } else { "" };
+    let x = if bits & 0o1 != 0 { "x" } else { "" };
+    format!("{}{}{}", r, w, x)
+}
+
+fn sym_full(bits: u16) -> String {
+    let r = if bits & 0o4 != 0 { "r" } else { "-" };
+    let w = if bits & 0o2 != 0 { "w" } else { "-" };
+    let x = if bits & 0o1 != 0 { "x" } else { "-" };
+    format!("{}{}{}", r, w, x)
+}
+
+fn mode_num(bits: u16) -> String {
+    let perms = format!("{}{}{}", num(bits >> 6), num(bits >> 3), num(bits));
+    match bits >> 9 {
+        0 => perms,
+        special => format!("{}{}", special, perms),
+    }
+}
+
+fn class_sym(bits: u16, special: bool, set: char) -> String {
+    let mut sym = sym_full(bits);
+    if special {
+        sym.pop();
+        sym.push(if bits & 0o1 != 0 {
+            set
+        } else {
+            set.to_ascii_uppercase()
+        });
+    }
+    sym
+}
+
+fn mode_sym(bits: u16) -> String {
+    format!(
+        "{}{}{}",
+        class_sym(bits >> 6, bits & 0o4000 != 0, 's'),
+        class_sym(bits >> 3, bits & 0o2000 != 0, 's'),
+        class_sym(bits, bits & 0o1000 != 0, 't')
+    )
+}
+
+fn table(out: &mut String, name: &str, len: u16, f: fn(u16) -> String) {
+    writeln!(out, "pub(crate) const {}: [&str; {}] = [", name, len).unwrap();
+    for bits in 0..len {
+        writeln!(out, "    {:?},", f(bits)).unwrap();
+    }
+    writeln!(out, "];").unwrap();
+}
+
+fn main() {
+    let mut out = String::new();
+    table(&mut out, "PERM_NUM", 0o10, num);
+    table(&mut out, "PERM_SYM", 0o10, sym);
+    table(&mut out, "PERM_SYM_FULL", 0o10, sym_full);
+    table(&mut out, "MODE_NUM", 0o10000, mode_num);
+    table(&mut out, "MODE_SYM", 0o10000, mode_sym);
+
+    let path = Path::new(&env::var("OUT_DIR").unwrap()).join("tables.rs");
+    fs::write(path, out).unwrap();
+
+    println!("cargo:rerun-if-changed=build.rs");
+}
@@ -1,57 +1,445 @@
+use std::{
+    ffi::OsString,
+    io::{self, BufRead, Write},
+    path::{Path, PathBuf},
+};
+
 use cchmod::{
     traits::{AsNum, AsSym},
-    Mode, Perm,
+    Mode, ParseError, Perm,
 };
 use clap::{crate_authors, crate_description, crate_name, crate_version, Parser};
 
 #[derive(Parser)]
 #[clap(name = crate_name!(), version = crate_version!(), author = crate_authors!(), about = crate_description!())]
 pub struct Opts {
-    input: String,
+    #[clap(
+        required_unless_present_any = &["file", "diff"],
+        help = "The permissions or modes, or - to read one per line from standard input"
+    )]
+    input: Vec<String>,
 
     #[clap(short, long, help = "Output the octal form")]
     num: bool,
     #[clap(short, long, help = "Output the symbolic form")]
     sym: bool,
+    #[clap(
+        short,
+        long,
+        conflicts_with_all = &["count", "grid"],
+        help = "Output the octal and symbolic forms"
+    )]
+    both: bool,
+    #[clap(
+        short,
+        long,
+        conflicts_with_all = &["num", "sym", "both", "count", "grid"],
+        help = "Output the symbolic form with a file type prefix, as in ls -l"
+    )]
+    long: bool,
+    #[clap(short, long, help = "Output the number of granted permissions")]
+    count: bool,
+    #[clap(short, long, help = "Output a table of the granted permissions")]
+    grid: bool,
+    #[clap(
+        short,
+        long,
+        parse(from_os_str),
+        conflicts_with = "input",
+        help = "Read the mode of a file"
+    )]
+    file: Option<PathBuf>,
+    #[clap(
+        long,
+        parse(from_os_str),
+        multiple_values = true,
+        conflicts_with_all = &["num", "sym", "both", "long", "count", "grid", "file"],
+        help = "Apply the mode to files"
+    )]
+    apply: Vec<PathBuf>,
+    #[clap(
+        long,
+        parse(try_from_str = parse_input),
+        number_of_values = 2,
+        value_names = &["FROM", "TO"],
+        conflicts_with_all = &[
+            "input", "num", "sym", "both", "long", "count", "grid", "file", "apply"
+        ],
+        help = "Output the changes from one mode to another"
+    )]
+    diff: Vec<Parsed>,
+    #[clap(short, long, help = "Suppress error messages")]
+    quiet: bool,
 }
 
 fn main() {
-    if let Err(message) = cli() {
-        eprintln!("{}", message);
+    let opts = Opts::try_parse().unwrap_or_else(|err| {
+        // The --diff values are parsed by clap, so their errors are reported before the flag is
+        // available.
+        if err.use_stderr() && quiet_requested(std::env::args_os().skip(1)) {
+            std::process::exit(2);
+        }
+        err.exit()
+    });
+
+    let quiet = opts.quiet;
+    if let Err(message) = cli(opts) {
+        report(&mut io::stderr(), &message, quiet);
         std::process::exit(1);
     }
 }
 
-fn cli() -> Result<(), String> {
-    let Opts { input, num, sym } = Opts::parse();
+fn cli(opts: Opts) -> Result<(), String> {
+    let Opts {
+        input,
+        num,
+        sym,
+        both,
+        long,
+        count,
+        grid,
+        file,
+        apply,
+        diff,
+        quiet,
+    } = opts;
 
-    let num = output_as_num(num, sym)?;
-    let output = match try_parse(&input) {
-        Some(Parsed::Mode(mode)) => convert(&mode, num),
-        Some(Parsed::Perm(perm)) => convert(&perm, num),
-        None => return Err(format!("{}: malformed permission or mode", input)),
+    if let [from, to] = diff.as_slice() {
+        println!("{}", as_diff(from, to)?);
+        return Ok(());
+    }
+
+    let (input, file_type) = match (file, input.as_slice()) {
+        (Some(path), _) if long => {
+            let (mode, file_type) = read_long(&path)?;
+            (Parsed::Mode(mode), file_type)
+        }
+        (Some(path), _) => (Parsed::Mode(read_mode(&path)?), '-'),
+        (None, [input]) if input != "-" => (parse_input(input)?, '-'),
+        (None, [_]) if apply.is_empty() => {
+            let output = output_kind(num, sym, both, long, count, grid)?;
+            let stdin = io::stdin();
+            return convert_lines(
+                stdin.lock(),
+                output,
+                &mut io::stdout(),
+                &mut io::stderr(),
+                quiet,
+            );
+        }
+        (None, [_]) => return Err("--apply cannot be used with standard input".to_string()),
+        (None, inputs) if apply.is_empty() => {
+            let output = output_kind(num, sym, both, long, count, grid)?;
+            return convert_all(inputs, output, &mut io::stdout(), &mut io::stderr(), quiet);
+        }
+        (None, _) => return Err("--apply requires a single mode".to_string()),
     };
 
+    if !apply.is_empty() {
+        return apply_to(&input, &apply, quiet);
+    }
+
+    let output = match output_kind(num, sym, both, long, count, grid)? {
+        Output::Long => as_long(&input, file_type)?,
+        output => render(&input, output)?,
+    };
     println!("{}", output);
 
     Ok(())
 }
 
-fn output_as_num(num: bool, sym: bool) -> Result<bool, String> {
-    if num && sym {
-        return Err("--num and --sym are exclusive".to_string());
-    } else if !(num || sym) {
-        return Err("--num or --sym must be supplied".to_string());
+/// Write an error message, unless `quiet` is set.
+fn report<W: Write>(w: &mut W, message: &str, quiet: bool) {
+    if !quiet {
+        // There is nowhere left to report a failure to write the error.
+        let _ = writeln!(w, "{}", message);
     }
+}
+
+/// Check whether the quiet flag is among the (unparsed) arguments, either as `--quiet` or within
+/// a group of short flags (e.g. `-nq`).
+fn quiet_requested<I: IntoIterator<Item = OsString>>(args: I) -> bool {
+    args.into_iter()
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .take_while(|arg| arg != "--")
+        .any(|arg| {
+            arg == "--quiet"
+                || (arg.starts_with('-')
+                    && !arg.starts_with("--")
+                    && arg[1..].chars().all(|c| c.is_ascii_alphabetic())
+                    && arg.contains('q'))
+        })
+}
 
-    Ok(num)
+/// Form in which the input is output.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Output {
+    Grid,
+    Count,
+    Num,
+    Sym,
+    Both,
+    Long,
 }
 
-fn convert<T: AsNum + AsSym>(v: &T, as_num: bool) -> String {
-    if as_num {
-        v.as_num()
+fn output_kind(
+    num: bool,
+    sym: bool,
+    both: bool,
+    long: bool,
+    count: bool,
+    grid: bool,
+) -> Result<Output, String> {
+    // The flags that cannot be used with --long are rejected by clap.
+    if long {
+        Ok(Output::Long)
+    } else if output_grid(grid, num, sym, count)? {
+        Ok(Output::Grid)
+    } else if output_count(count, num, sym)? {
+        Ok(Output::Count)
     } else {
-        v.as_sym()
+        output_as_num(num, sym, both)
+    }
+}
+
+fn render(input: &Parsed, output: Output) -> Result<String, String> {
+    match output {
+        Output::Grid => as_grid(input),
+        Output::Long => as_long(input, '-'),
+        Output::Count => Ok(count_bits(input).to_string()),
+        Output::Num | Output::Sym | Output::Both => Ok(match input {
+            Parsed::Mode(mode) => convert(mode, output),
+            Parsed::Perm(perm) => convert(perm, output),
+        }),
+    }
+}
+
+/// Convert each non-blank line of `reader`, writing the results to `out` and reporting each
+/// failure, with its line number, to `err` (unless `quiet` is set).
+fn convert_lines<R: BufRead, O: Write, E: Write>(
+    reader: R,
+    output: Output,
+    out: &mut O,
+    err: &mut E,
+    quiet: bool,
+) -> Result<(), String> {
+    let (mut total, mut failed) = (0, 0);
+    for (i, line) in reader.lines().enumerate() {
+        let converted = line.map_err(|err| err.to_string()).and_then(|line| {
+            let line = line.trim();
+            if line.is_empty() {
+                return Ok(None);
+            }
+            render(&parse_input(line)?, output).map(Some)
+        });
+
+        match converted {
+            Ok(None) => continue,
+            Ok(Some(converted)) => writeln!(out, "{}", converted).map_err(|err| err.to_string())?,
+            Err(message) => {
+                report(err, &format!("line {}: {}", i + 1, message), quiet);
+                failed += 1;
+            }
+        }
+        total += 1;
+    }
+
+    match failed {
+        0 => Ok(()),
+        failed => Err(format!("failed to convert {} of {} lines", failed, total)),
+    }
+}
+
+/// Convert each of `inputs`, writing the results to `out` and reporting each failure, tagged with
+/// the input, to `err` (unless `quiet` is set).
+fn convert_all<O: Write, E: Write>(
+    inputs: &[String],
+    output: Output,
+    out: &mut O,
+    err: &mut E,
+    quiet: bool,
+) -> Result<(), String> {
+    let mut failed = 0;
+    for input in inputs {
+        match parse_input(input).and_then(|parsed| render(&parsed, output)) {
+            Ok(converted) => writeln!(out, "{}", converted).map_err(|err| err.to_string())?,
+            Err(message) => {
+                report(err, &format!("'{}': {}", input, message), quiet);
+                failed += 1;
+            }
+        }
+    }
+
+    match failed {
+        0 => Ok(()),
+        failed => Err(format!(
+            "failed to convert {} of {} inputs",
+            failed,
+            inputs.len()
+        )),
+    }
+}
+
+fn output_as_num(num: bool, sym: bool, both: bool) -> Result<Output, String> {
+    if both && (num || sym) {
+        return Err("--both cannot be used with --num or --sym".to_string());
+    } else if num && sym {
+        return Err("--num and --sym are exclusive".to_string());
+    } else if !(num || sym || both) {
+        return Err("--num, --sym, or --both must be supplied".to_string());
+    }
+
+    Ok(if both {
+        Output::Both
+    } else if num {
+        Output::Num
+    } else {
+        Output::Sym
+    })
+}
+
+fn output_count(count: bool, num: bool, sym: bool) -> Result<bool, String> {
+    if count && (num || sym) {
+        return Err("--count cannot be used with --num or --sym".to_string());
+    }
+
+    Ok(count)
+}
+
+fn output_grid(grid: bool, num: bool, sym: bool, count: bool) -> Result<bool, String> {
+    if grid && (num || sym || count) {
+        return Err("--grid cannot be used with --num, --sym, or --count".to_string());
+    }
+
+    Ok(grid)
+}
+
+fn as_grid(input: &Parsed) -> Result<String, String> {
+    match input {
+        Parsed::Mode(mode) => Ok(mode.as_grid()),
+        Parsed::Perm(_) => Err("--grid requires a mode".to_string()),
+    }
+}
+
+#[cfg(unix)]
+fn read_mode(path: &Path) -> Result<Mode, String> {
+    use std::os::unix::fs::PermissionsExt;
+
+    std::fs::metadata(path)
+        .map(|metadata| Mode::from(metadata.permissions().mode()))
+        .map_err(|err| format!("cannot read mode of '{}': {}", path.display(), err))
+}
+
+#[cfg(not(unix))]
+fn read_mode(_path: &Path) -> Result<Mode, String> {
+    Err("--file is only supported on Unix".to_string())
+}
+
+/// Read the mode and the `ls -l` file type character of a file, without following symbolic
+/// links.
+#[cfg(unix)]
+fn read_long(path: &Path) -> Result<(Mode, char), String> {
+    use std::os::unix::fs::MetadataExt;
+
+    let metadata = std::fs::symlink_metadata(path)
+        .map_err(|err| format!("cannot read mode of '{}': {}", path.display(), err))?;
+    // As in ls, an unknown file type is shown as '?'.
+    let (mode, file_type) = Mode::from_raw(metadata.mode());
+    Ok((mode, file_type.map_or('?', |file_type| file_type.as_char())))
+}
+
+#[cfg(not(unix))]
+fn read_long(_path: &Path) -> Result<(Mode, char), String> {
+    Err("--file is only supported on Unix".to_string())
+}
+
+fn apply_to(input: &Parsed, paths: &[PathBuf], quiet: bool) -> Result<(), String> {
+    let mode = match input {
+        Parsed::Mode(mode) => mode,
+        Parsed::Perm(_) => return Err("--apply requires a mode".to_string()),
+    };
+
+    match apply_all(mode, paths, &mut io::stdout(), &mut io::stderr(), quiet) {
+        0 => Ok(()),
+        failed => Err(format!(
+            "failed to apply mode to {} of {} files",
+            failed,
+            paths.len()
+        )),
+    }
+}
+
+/// Apply `mode` to each of `paths`, describing each change to `out` and reporting each failure to
+/// `err` (unless `quiet` is set), and return the number of failures.
+fn apply_all<O: Write, E: Write>(
+    mode: &Mode,
+    paths: &[PathBuf],
+    out: &mut O,
+    err: &mut E,
+    quiet: bool,
+) -> usize {
+    let mut failed = 0;
+    for path in paths {
+        match apply_mode(path, mode) {
+            // The mode has already been applied, so a failure to describe it is not reported.
+            Ok(changes) => {
+                let _ = writeln!(out, "{}", changes);
+            }
+            Err(message) => {
+                report(err, &message, quiet);
+                failed += 1;
+            }
+        }
+    }
+    failed
+}
+
+#[cfg(unix)]
+fn apply_mode(path: &Path, mode: &Mode) -> Result<String, String> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mut permissions = std::fs::metadata(path)
+        .map_err(|err| format!("cannot read mode of '{}': {}", path.display(), err))?
+        .permissions();
+    let current = Mode::from(permissions.mode());
+    permissions.set_mode(u32::from(*mode));
+    std::fs::set_permissions(path, permissions)
+        .map_err(|err| format!("cannot change mode of '{}': {}", path.display(), err))?;
+
+    Ok(current.as_chmod_changes(mode, &path.display().to_string()))
+}
+
+#[cfg(not(unix))]
+fn apply_mode(_path: &Path, _mode: &Mode) -> Result<String, String> {
+    Err("--apply is only supported on Unix".to_string())
+}
+
+fn as_diff(from: &Parsed, to: &Parsed) -> Result<String, String> {
+    match (from, to) {
+        (Parsed::Mode(from), Parsed::Mode(to)) => Ok(from.diff(to).as_sym()),
+        _ => Err("--diff requires two modes".to_string()),
+    }
+}
+
+fn as_long(input: &Parsed, file_type: char) -> Result<String, String> {
+    match input {
+        Parsed::Mode(mode) => Ok(format!("{}{}", file_type, mode.as_sym())),
+        Parsed::Perm(_) => Err("--long requires a mode".to_string()),
+    }
+}
+
+fn count_bits(input: &Parsed) -> u32 {
+    match input {
+        Parsed::Mode(mode) => mode.total_bits(),
+        Parsed::Perm(perm) => perm.count(),
+    }
+}
+
+fn convert<T: AsNum + AsSym>(v: &T, output: Output) -> String {
+    match output {
+        Output::Num => v.as_num(),
+        Output::Both => format!("{}\t{}", v.as_num(), v.as_sym()),
+        _ => v.as_sym(),
     }
 }
 
@@ -61,13 +449,26 @@ enum Parsed {
     Perm(Perm),
 }
 
-fn try_parse(input: &str) -> Option<Parsed> {
-    if let Ok(mode) = Mode::from_num(input).or_else(|_| Mode::from_sym(input)) {
-        Some(Parsed::Mode(mode))
-    } else if let Ok(perm) = Perm::from_num(input).or_else(|_| Perm::from_sym_full(input)) {
-        Some(Parsed::Perm(perm))
+fn parse_input(input: &str) -> Result<Parsed, String> {
+    try_parse(input).map_err(|err| {
+        format!(
+            "malformed permission or mode\n{}",
+            err.render_with_input(input)
+        )
+    })
+}
+
+fn try_parse(input: &str) -> Result<Parsed, ParseError> {
+    // Permissions are one digit or three symbols long; anything else is treated as a mode so that
+    // the error reported is the most relevant one.
+    let numeric = input.starts_with(|c: char| c.is_ascii_digit());
+    let len = input.chars().count();
+    if numeric && len == Perm::NUM_LEN {
+        Perm::from_num(input).map(Parsed::Perm)
+    } else if !numeric && len <= Perm::SYM_FULL_LEN {
+        Perm::from_sym_full(input).map(Parsed::Perm)
     } else {
-        None
+        Mode::parse(input).map(Parsed::Mode)
     }
 }
 
@@ -76,23 +477,422 @@ mod test {
 
     #[test]
     fn test_output_as_num() {
+        use super::Output;
+
+        macro_rules! test {
+            ($c:expr, $n:expr, $s:expr, $b:expr) => {
+                assert_eq!($c, super::output_as_num($n, $s, $b))
+            };
+        }
+
+        test!(Ok(Output::Num), true, false, false);
+        test!(Ok(Output::Sym), false, true, false);
+        test!(Ok(Output::Both), false, false, true);
+
+        test!(
+            Err("--num and --sym are exclusive".to_string()),
+            true,
+            true,
+            false
+        );
+        test!(
+            Err("--num, --sym, or --both must be supplied".to_string()),
+            false,
+            false,
+            false
+        );
+
+        let err = Err("--both cannot be used with --num or --sym".to_string());
+        test!(err.clone(), true, false, true);
+        test!(err.clone(), false, true, true);
+        test!(err, true, true, true);
+    }
+
+    #[test]
+    fn test_convert() {
+        use super::Output;
+        use cchmod::{Mode, Perm};
+
+        let mode = Mode::from_num("755").unwrap();
+        assert_eq!("755", super::convert(&mode, Output::Num));
+        assert_eq!("rwxr-xr-x", super::convert(&mode, Output::Sym));
+        assert_eq!("755\trwxr-xr-x", super::convert(&mode, Output::Both));
+        assert_eq!(
+            "5\tr-x",
+            super::convert(&Perm::from_num("5").unwrap(), Output::Both)
+        );
+    }
+
+    #[test]
+    fn test_output_count() {
+        macro_rules! test {
+            ($c:expr, $count:expr, $n:expr, $s:expr) => {
+                assert_eq!($c, super::output_count($count, $n, $s))
+            };
+        }
+
+        test!(Ok(true), true, false, false);
+        test!(Ok(false), false, true, false);
+        test!(Ok(false), false, false, false);
+
+        let err = Err("--count cannot be used with --num or --sym".to_string());
+        test!(err.clone(), true, true, false);
+        test!(err, true, false, true);
+    }
+
+    #[test]
+    fn test_output_grid() {
+        macro_rules! test {
+            ($c:expr, $grid:expr, $n:expr, $s:expr, $count:expr) => {
+                assert_eq!($c, super::output_grid($grid, $n, $s, $count))
+            };
+        }
+
+        test!(Ok(true), true, false, false, false);
+        test!(Ok(false), false, true, false, false);
+        test!(Ok(false), false, false, false, true);
+
+        let err = Err("--grid cannot be used with --num, --sym, or --count".to_string());
+        test!(err.clone(), true, true, false, false);
+        test!(err.clone(), true, false, true, false);
+        test!(err, true, false, false, true);
+    }
+
+    #[test]
+    fn test_as_grid() {
         macro_rules! test {
-            ($c:expr, $n:expr, $s:expr) => {
-                assert_eq!($c, super::output_as_num($n, $s))
+            ($c:expr, $input:expr) => {
+                assert_eq!($c, super::as_grid(&super::try_parse($input).unwrap()))
             };
         }
 
-        test!(Ok(true), true, false);
-        test!(Ok(false), false, true);
+        test!(
+            Ok("      r w x\nuser  r w x\ngroup r   x\nother     x".to_string()),
+            "751"
+        );
+        test!(Err("--grid requires a mode".to_string()), "7");
+        test!(Err("--grid requires a mode".to_string()), "r-x");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_read_mode() -> Result<(), Box<dyn std::error::Error>> {
+        use std::{fs, os::unix::fs::PermissionsExt};
+
+        use cchmod::Mode;
+
+        let path = std::env::temp_dir().join(format!("cchmod-test-{}", std::process::id()));
+        fs::write(&path, "")?;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o754))?;
+        let mode = super::read_mode(&path);
+        fs::remove_file(&path)?;
+        assert_eq!(Ok(Mode::from_num("754")?), mode);
+
+        let err = super::read_mode(&path).unwrap_err();
+        assert!(err.starts_with(&format!("cannot read mode of '{}': ", path.display())));
+
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_read_long() -> Result<(), Box<dyn std::error::Error>> {
+        use std::{fs, os::unix::fs::PermissionsExt};
+
+        use cchmod::Mode;
+
+        let dir = std::env::temp_dir().join(format!("cchmod-test-long-{}", std::process::id()));
+        fs::create_dir(&dir)?;
+        fs::set_permissions(&dir, fs::Permissions::from_mode(0o1755))?;
+        let file = dir.join("file");
+        fs::write(&file, "")?;
+        fs::set_permissions(&file, fs::Permissions::from_mode(0o640))?;
+        let link = dir.join("link");
+        std::os::unix::fs::symlink(&file, &link)?;
+
+        let results = (
+            super::read_long(&dir),
+            super::read_long(&file),
+            super::read_long(&link),
+        );
+        fs::remove_dir_all(&dir)?;
+
+        assert_eq!(Ok((Mode::from_num("1755")?, 'd')), results.0);
+        assert_eq!(Ok((Mode::from_num("640")?, '-')), results.1);
+        assert_eq!('l', results.2?.1);
+        assert!(super::read_long(&dir).is_err());
+
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_apply_all() -> Result<(), Box<dyn std::error::Error>> {
+        use std::{fs, os::unix::fs::PermissionsExt};
+
+        use cchmod::Mode;
+
+        let dir = std::env::temp_dir().join(format!("cchmod-test-apply-{}", std::process::id()));
+        fs::create_dir(&dir)?;
+        let (a, b, missing) = (dir.join("a"), dir.join("b"), dir.join("missing"));
+        for path in [&a, &b] {
+            fs::write(path, "")?;
+            fs::set_permissions(path, fs::Permissions::from_mode(0o755))?;
+        }
+
+        let (mut out, mut err) = (Vec::new(), Vec::new());
+        let paths = vec![a.clone(), missing.clone(), b.clone()];
+        let failed = super::apply_all(&Mode::from_num("4644")?, &paths, &mut out, &mut err, false);
+        let modes = (super::read_mode(&a), super::read_mode(&b));
+        fs::remove_dir_all(&dir)?;
+
+        assert_eq!(1, failed);
+        assert_eq!(Ok(Mode::from_num("4644")?), modes.0);
+        assert_eq!(Ok(Mode::from_num("4644")?), modes.1);
+        assert_eq!(
+            format!(
+                "mode of '{}' changed from 0755 (rwxr-xr-x) to 4644 (rwSr--r--)\n\
+                 mode of '{}' changed from 0755 (rwxr-xr-x) to 4644 (rwSr--r--)\n",
+                a.display(),
+                b.display()
+            ),
+            String::from_utf8(out)?
+        );
+        let err = String::from_utf8(err)?;
+        assert!(err.starts_with(&format!("cannot read mode of '{}': ", missing.display())));
+        assert_eq!(1, err.lines().count());
 
-        test!(Err("--num and --sym are exclusive".to_string()), true, true);
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_to() {
+        assert_eq!(
+            Err("--apply requires a mode".to_string()),
+            super::apply_to(&super::try_parse("rwx").unwrap(), &[], false)
+        );
+    }
+
+    #[test]
+    fn test_as_diff() {
+        macro_rules! test {
+            ($c:expr, $from:expr, $to:expr) => {
+                assert_eq!(
+                    $c,
+                    super::as_diff(
+                        &super::try_parse($from).unwrap(),
+                        &super::try_parse($to).unwrap()
+                    )
+                )
+            };
+        }
+
+        test!(Ok("u-x,g-wx,o-wx".to_string()), "777", "644");
+        test!(Ok("u-x,g-wx,o-wx".to_string()), "rwxrwxrwx", "644");
+        test!(Ok("g+w".to_string()), "755", "rwxrwxr-x");
+        test!(Ok("".to_string()), "755", "755");
+        test!(Ok("u-s".to_string()), "4755", "755");
+        test!(Ok("g+s,o+t".to_string()), "755", "rwxr-sr-t");
+        test!(Err("--diff requires two modes".to_string()), "7", "644");
+        test!(Err("--diff requires two modes".to_string()), "755", "r-x");
+    }
+
+    #[test]
+    fn test_diff_args() {
+        use clap::Parser;
+
+        let opts = super::Opts::try_parse_from(["cchmod", "--diff", "777", "rw-r--r--"]).unwrap();
+        assert!(opts.input.is_empty());
+        assert_eq!(
+            vec![
+                super::try_parse("777").unwrap(),
+                super::try_parse("644").unwrap()
+            ],
+            opts.diff
+        );
+
+        let err = super::Opts::try_parse_from(["cchmod", "--diff", "777", "rw-r-zr--"])
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(
+            err.contains("rw-r-zr--\n     ^ unexpected 'z', expected one of 'x', 's', 'S', '-'")
+        );
+
+        let opts = super::Opts::try_parse_from(["cchmod", "--diff", "4755", "755"]).unwrap();
+        assert_eq!(
+            Ok("u-s".to_string()),
+            super::as_diff(&opts.diff[0], &opts.diff[1])
+        );
+
+        assert!(super::Opts::try_parse_from(["cchmod", "--diff", "777"]).is_err());
+        assert!(super::Opts::try_parse_from(["cchmod", "-n", "--diff", "777", "644"]).is_err());
+    }
+
+    #[test]
+    fn test_output_kind() {
+        use super::Output;
+
+        macro_rules! test {
+            ($c:expr, $n:expr, $s:expr, $b:expr, $l:expr, $count:expr, $grid:expr) => {
+                assert_eq!($c, super::output_kind($n, $s, $b, $l, $count, $grid))
+            };
+        }
+
+        test!(Ok(Output::Grid), false, false, false, false, false, true);
+        test!(Ok(Output::Count), false, false, false, false, true, false);
+        test!(Ok(Output::Num), true, false, false, false, false, false);
+        test!(Ok(Output::Sym), false, true, false, false, false, false);
+        test!(Ok(Output::Both), false, false, true, false, false, false);
+        test!(Ok(Output::Long), false, false, false, true, false, false);
         test!(
-            Err("--num or --sym must be supplied".to_string()),
+            Err("--num and --sym are exclusive".to_string()),
+            true,
+            true,
+            false,
+            false,
             false,
             false
         );
     }
 
+    #[test]
+    fn test_convert_lines() {
+        use super::Output;
+
+        macro_rules! test {
+            ($c:expr, $out:expr, $err:expr, $output:expr, $input:expr) => {
+                let (mut out, mut err) = (Vec::new(), Vec::new());
+                assert_eq!(
+                    $c,
+                    super::convert_lines(&$input[..], $output, &mut out, &mut err, false)
+                );
+                assert_eq!($out, String::from_utf8(out).unwrap());
+                assert_eq!($err, String::from_utf8(err).unwrap());
+            };
+        }
+
+        test!(
+            Ok(()),
+            "755\n644\n5\n",
+            "",
+            Output::Num,
+            b"rwxr-xr-x\n\nrw-r--r--\n  \nr-x\n"
+        );
+        test!(Ok(()), "rwxr-xr-x\n", "", Output::Sym, b"755\r\n");
+        test!(Ok(()), "", "", Output::Sym, b"");
+        test!(
+            Err("failed to convert 1 of 3 lines".to_string()),
+            "751\n777\n",
+            "line 2: malformed permission or mode\n75\n  ^ expected 3 digits for a mode, found 2\n",
+            Output::Num,
+            b"rwxr-x--x\n75\n\n777\n"
+        );
+        test!(
+            Err("failed to convert 1 of 1 lines".to_string()),
+            "",
+            "line 1: --grid requires a mode\n",
+            Output::Grid,
+            b"7\n"
+        );
+    }
+
+    #[test]
+    fn test_convert_all() {
+        use super::Output;
+
+        macro_rules! test {
+            ($c:expr, $out:expr, $err:expr, $output:expr, [$($input:expr),*]) => {
+                let (mut out, mut err) = (Vec::new(), Vec::new());
+                let inputs: Vec<String> = vec![$($input.to_string()),*];
+                assert_eq!(
+                    $c,
+                    super::convert_all(&inputs, $output, &mut out, &mut err, false)
+                );
+                assert_eq!($out, String::from_utf8(out).unwrap());
+                assert_eq!($err, String::from_utf8(err).unwrap());
+            };
+        }
+
+        test!(
+            Ok(()),
+            "rwxr-xr-x\nrw-r--r--\nrw-------\n",
+            "",
+            Output::Sym,
+            ["755", "644", "600"]
+        );
+        test!(Ok(()), "755\n5\n", "", Output::Num, ["rwxr-xr-x", "r-x"]);
+        test!(Ok(()), "", "", Output::Num, []);
+        test!(
+            Err("failed to convert 2 of 4 inputs".to_string()),
+            "rwxr-xr-x\nrw-r--r--\n",
+            "'75': malformed permission or mode\n75\n  ^ expected 3 digits for a mode, found 2\n\
+             '-': malformed permission or mode\n-\n ^ unexepected end-of-input\n",
+            Output::Sym,
+            ["755", "75", "644", "-"]
+        );
+    }
+
+    #[test]
+    fn test_as_long() {
+        macro_rules! test {
+            ($c:expr, $input:expr, $file_type:expr) => {
+                assert_eq!(
+                    $c,
+                    super::as_long(&super::try_parse($input).unwrap(), $file_type)
+                )
+            };
+        }
+
+        test!(Ok("-rwxr-xr-x".to_string()), "755", '-');
+        test!(Ok("drwxrwxrwt".to_string()), "1777", 'd');
+        test!(Ok("lrwxrwxrwx".to_string()), "rwxrwxrwx", 'l');
+        test!(Err("--long requires a mode".to_string()), "7", '-');
+    }
+
+    #[test]
+    fn test_count_bits() {
+        macro_rules! test {
+            ($c:expr, $input:expr) => {
+                assert_eq!($c, super::count_bits(&super::try_parse($input).unwrap()))
+            };
+        }
+
+        test!(6, "751");
+        test!(9, "rwxrwxrwx");
+        test!(0, "000");
+        test!(2, "5");
+        test!(1, "-w-");
+    }
+
+    #[test]
+    fn test_report() {
+        let mut out = Vec::new();
+        super::report(&mut out, "--num, --sym, or --both must be supplied", true);
+        assert!(out.is_empty());
+
+        super::report(&mut out, "--num, --sym, or --both must be supplied", false);
+        assert_eq!(b"--num, --sym, or --both must be supplied\n".to_vec(), out);
+    }
+
+    #[test]
+    fn test_quiet_requested() {
+        macro_rules! test {
+            ($c:expr, [$($arg:expr),*]) => {
+                assert_eq!($c, super::quiet_requested(vec![$($arg.into()),*]))
+            };
+        }
+
+        test!(true, ["-q", "-n", "rwxr-zr-x"]);
+        test!(true, ["--quiet", "777"]);
+        test!(true, ["-nq", "777"]);
+        test!(true, ["777", "-sq"]);
+        test!(false, ["-n", "777"]);
+        test!(false, ["--", "-q"]);
+        test!(false, ["-n", "-w-"]);
+        test!(false, []);
+    }
+
     #[test]
     fn test_try_parse() {
         use super::Parsed::*;
@@ -100,13 +900,13 @@ mod test {
 
         macro_rules! test {
             ($c:expr, $input:expr) => {
-                assert_eq!(Some($c), super::try_parse($input))
+                assert_eq!(Ok($c), super::try_parse($input))
             };
         }
 
         macro_rules! test_fail {
             ($input:expr) => {
-                assert_eq!(None, super::try_parse($input))
+                assert!(super::try_parse($input).is_err())
             };
         }
 
@@ -126,7 +926,10 @@ mod test {
                     read: true,
                     write: true,
                     execute: true
-                }
+                },
+                setuid: false,
+                setgid: false,
+                sticky: false
             }),
             "rwxrwxrwx"
         );
@@ -146,7 +949,10 @@ mod test {
                     read: true,
                     write: true,
                     execute: true
-                }
+                },
+                setuid: false,
+                setgid: false,
+                sticky: false
             }),
             "777"
         );
@@ -167,6 +973,9 @@ mod test {
             "7"
         );
 
+        test!(Mode(Mode::from_num("4444").unwrap()), "4444");
+        test!(Mode(Mode::from_sym("rwsr-xr-t").unwrap()), "rwsr-xr-t");
+
         test_fail!("");
         test_fail!("rx");
         test_fail!("rwxx");
@@ -174,6 +983,27 @@ mod test {
         test_fail!("8");
         test_fail!("77");
         test_fail!("585");
-        test_fail!("4444");
+        test_fail!("8444");
+        test_fail!("44444");
+    }
+
+    #[test]
+    fn test_parse_input() {
+        use clap::Parser;
+
+        // Positional inputs are parsed after the arguments, so that each can fail separately.
+        let opts = super::Opts::try_parse_from(["cchmod", "-n", "rwxr-zr-x", "755"]).unwrap();
+        assert_eq!(vec!["rwxr-zr-x", "755"], opts.input);
+
+        let err = super::parse_input("rwxr-zr-x").unwrap_err();
+        assert!(
+            err.contains("rwxr-zr-x\n     ^ unexpected 'z', expected one of 'x', 's', 'S', '-'")
+        );
+
+        let err = super::parse_input("75").unwrap_err();
+        assert_eq!(
+            "malformed permission or mode\n75\n  ^ expected 3 digits for a mode, found 2",
+            err
+        );
     }
 }
@@ -0,0 +1,170 @@
+//! [`ModeFlags`], a bit flag representation of a [`Mode`].
+
+use core::ops::{BitAnd, BitOr};
+
+use crate::Mode;
+
+/// Bit flag representation of a [`Mode`], with one bit per permission or special bit in the same
+/// layout as the octal form (e.g. `0o4755`).
+///
+/// # Examples
+///
+/// ```
+/// use cchmod::{flags::ModeFlags, Mode};
+///
+/// let flags = ModeFlags::USER_READ | ModeFlags::USER_WRITE | ModeFlags::GROUP_READ;
+/// assert_eq!(0o640, flags.bits());
+/// assert_eq!("640", Mode::from(flags).as_num());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ModeFlags(u16);
+
+impl ModeFlags {
+    /// No permissions.
+    pub const EMPTY: Self = Self(0);
+    /// Every permission and special bit.
+    pub const ALL: Self = Self(0o7777);
+
+    /// The setuid bit.
+    pub const SETUID: Self = Self(0o4000);
+    /// The setgid bit.
+    pub const SETGID: Self = Self(0o2000);
+    /// The sticky bit.
+    pub const STICKY: Self = Self(0o1000);
+
+    /// Read permission for the owning user.
+    pub const USER_READ: Self = Self(0o400);
+    /// Write permission for the owning user.
+    pub const USER_WRITE: Self = Self(0o200);
+    /// Execute permission for the owning user.
+    pub const USER_EXECUTE: Self = Self(0o100);
+    /// Read permission for the group.
+    pub const GROUP_READ: Self = Self(0o040);
+    /// Write permission for the group.
+    pub const GROUP_WRITE: Self = Self(0o020);
+    /// Execute permission for the group.
+    pub const GROUP_EXECUTE: Self = Self(0o010);
+    /// Read permission for all other users.
+    pub const OTHER_READ: Self = Self(0o004);
+    /// Write permission for all other users.
+    pub const OTHER_WRITE: Self = Self(0o002);
+    /// Execute permission for all other users.
+    pub const OTHER_EXECUTE: Self = Self(0o001);
+
+    /// Get the raw bits of the [`ModeFlags`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::flags::ModeFlags;
+    ///
+    /// assert_eq!(0o100, ModeFlags::USER_EXECUTE.bits());
+    /// ```
+    #[inline]
+    pub const fn bits(&self) -> u16 {
+        self.0
+    }
+
+    /// Create [`ModeFlags`] from raw bits, discarding any bits that do not correspond to a
+    /// permission or special bit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::flags::ModeFlags;
+    ///
+    /// assert_eq!(ModeFlags::ALL, ModeFlags::from_bits_truncate(0o17777));
+    /// assert_eq!(ModeFlags::STICKY, ModeFlags::from_bits_truncate(0o11000));
+    /// ```
+    #[inline]
+    pub const fn from_bits_truncate(bits: u16) -> Self {
+        Self(bits & Self::ALL.0)
+    }
+
+    /// Check whether all of the flags in `other` are set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::flags::ModeFlags;
+    ///
+    /// let flags = ModeFlags::USER_READ | ModeFlags::USER_WRITE;
+    /// assert!(flags.contains(ModeFlags::USER_READ));
+    /// assert!(flags.contains(ModeFlags::USER_READ | ModeFlags::USER_WRITE));
+    /// assert!(!flags.contains(ModeFlags::USER_READ | ModeFlags::GROUP_READ));
+    /// ```
+    #[inline]
+    pub const fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for ModeFlags {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitAnd for ModeFlags {
+    type Output = Self;
+
+    #[inline]
+    fn bitand(self, rhs: Self) -> Self {
+        Self(self.0 & rhs.0)
+    }
+}
+
+impl From<Mode> for ModeFlags {
+    #[inline]
+    fn from(mode: Mode) -> Self {
+        Self::from_bits_truncate(mode.bits())
+    }
+}
+
+impl From<ModeFlags> for Mode {
+    #[inline]
+    fn from(flags: ModeFlags) -> Self {
+        Self::from_bits(flags.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_mode_flags() -> Result<(), Box<dyn std::error::Error>> {
+        let flags = ModeFlags::USER_READ
+            | ModeFlags::USER_WRITE
+            | ModeFlags::USER_EXECUTE
+            | ModeFlags::GROUP_READ
+            | ModeFlags::GROUP_EXECUTE
+            | ModeFlags::OTHER_READ
+            | ModeFlags::OTHER_EXECUTE;
+        assert_eq!(0o755, flags.bits());
+        assert_eq!(Mode::from_num("755")?, Mode::from(flags));
+        assert_eq!(flags, ModeFlags::from(Mode::from_num("755")?));
+
+        assert!(flags.contains(ModeFlags::GROUP_READ | ModeFlags::OTHER_EXECUTE));
+        assert!(!flags.contains(ModeFlags::GROUP_WRITE));
+        assert_eq!(
+            ModeFlags::USER_WRITE,
+            flags & (ModeFlags::USER_WRITE | ModeFlags::GROUP_WRITE)
+        );
+
+        let flags = flags | ModeFlags::SETUID;
+        assert_eq!(Mode::from_num("4755")?, Mode::from(flags));
+        assert!(!flags.contains(ModeFlags::SETGID | ModeFlags::STICKY));
+
+        for bits in 0..0o10000 {
+            let flags = ModeFlags::from(Mode::from_bits(bits));
+            assert_eq!(bits, flags.bits());
+            assert_eq!(Mode::from_bits(bits), Mode::from(flags));
+        }
+
+        Ok(())
+    }
+}
@@ -1,12 +1,60 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+pub mod flags;
 pub mod perm;
 pub mod traits;
 
-use std::str::Chars;
+#[cfg(feature = "test-util")]
+pub mod test_util;
+
+mod ops;
+
+mod tables {
+    //! Representations of every [`Perm`](crate::Perm) and [`Mode`](crate::Mode), indexed by
+    //! their octal values and generated by the build script.
+
+    // The tables must be constants to be usable in const functions.
+    #![allow(clippy::large_const_arrays)]
+
+    include!(concat!(env!("OUT_DIR"), "/tables.rs"));
+}
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::{Add, AddAssign, BitAnd, BitOr, BitXor, Not};
+use core::str::Chars;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+#[cfg(feature = "std")]
+use std::ffi::OsStr;
 
+#[cfg(feature = "std")]
 use thiserror::Error;
 
+use crate::traits::ModeVisitor;
+
 /// File system object mode.
-#[derive(Debug, PartialEq)]
+///
+/// Modes are ordered by the octal values of the user, group, and other classes in turn (so
+/// `755 < 761`), then by the special bits. This is a numeric ordering, not a comparison of the
+/// permissions granted (see [`Mode::contains`]).
+///
+/// # Examples
+///
+/// ```
+/// use cchmod::Mode;
+///
+/// let mode = |num| Mode::from_num(num).unwrap();
+/// assert!(mode("755") < mode("761"));
+/// assert!(mode("755") < mode("4755"));
+/// assert!(mode("4644") < mode("755"));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Mode {
     /// Permission set for the owning user.
     pub user: Perm,
@@ -14,12 +62,32 @@ pub struct Mode {
     pub group: Perm,
     /// Permission set for all other users.
     pub other: Perm,
+    /// Flag indicating whether the *setuid* bit is set.
+    pub setuid: bool,
+    /// Flag indicating whether the *setgid* bit is set.
+    pub setgid: bool,
+    /// Flag indicating whether the *sticky* bit is set.
+    pub sticky: bool,
 }
 
 /// File system object permissions.
 ///
 /// See [`perm`] for predefined constant values.
-#[derive(Debug, PartialEq)]
+///
+/// Permissions are ordered by their octal values (so `RWX > RW > R`). This is a numeric ordering,
+/// not a comparison of the permissions granted (see [`Perm::contains`]): `RW > RX` although
+/// neither grants all of the permissions of the other.
+///
+/// # Examples
+///
+/// ```
+/// use cchmod::{perm::*, Perm};
+///
+/// assert!(Perm::from_num("7").unwrap() > Perm::from_num("5").unwrap());
+/// assert!(RW > RX);
+/// assert_eq!(Some(RWX), [R, RWX, WX].iter().copied().max());
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Perm {
     /// Flag indicating whether *read* permission is granted.
     pub read: bool,
@@ -29,12 +97,134 @@ pub struct Perm {
     pub execute: bool,
 }
 
+/// Partial [`Mode`], which sets the permissions of only some classes.
+#[derive(Debug, PartialEq)]
+pub struct ModeMask {
+    /// Permission set for the owning user, or [`None`] if unaffected.
+    pub user: Option<Perm>,
+    /// Permission set for the group, or [`None`] if unaffected.
+    pub group: Option<Perm>,
+    /// Permission set for all other users, or [`None`] if unaffected.
+    pub other: Option<Perm>,
+    /// Flag indicating whether the *setuid* bit is set, or [`None`] if unaffected.
+    pub setuid: Option<bool>,
+    /// Flag indicating whether the *setgid* bit is set, or [`None`] if unaffected.
+    pub setgid: Option<bool>,
+    /// Flag indicating whether the *sticky* bit is set, or [`None`] if unaffected.
+    pub sticky: Option<bool>,
+}
+
+/// Wrapper for a [`Perm`] that is serialized as a structure of flags (e.g. the TOML inline table
+/// `{ read = true, write = false, execute = true }`).
+#[cfg(feature = "serde")]
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(transparent)]
+pub struct StructuredPerm(#[serde(with = "PermDef")] pub Perm);
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(remote = "Perm")]
+struct PermDef {
+    read: bool,
+    write: bool,
+    execute: bool,
+}
+
+/// Relation of a user to a file system object, which determines the class of a [`Mode`] that
+/// applies to them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OwnershipRelation {
+    /// The user owns the object.
+    Owner,
+    /// The user is a member of the object's group.
+    Group,
+    /// The user is neither the owner nor a member of the group.
+    Other,
+}
+
+/// Permission class of a [`Mode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Class {
+    /// The owning user.
+    User,
+    /// The owning group.
+    Group,
+    /// All other users.
+    Other,
+}
+
+/// Single permission of a [`Perm`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PermBit {
+    /// Read permission.
+    Read,
+    /// Write permission.
+    Write,
+    /// Execute permission.
+    Execute,
+}
+
+/// Indicator appended by `ls` to the symbolic form of a [`Mode`] (see
+/// [`Mode::from_sym_with_acl`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AclIndicator {
+    /// The file has an access control list (`+`).
+    Acl,
+    /// The file has an SELinux security context but no other alternate access method (`.`).
+    SecurityContext,
+}
+
+/// Type of a file system object, as encoded in the bits above the permissions and special bits of
+/// a raw mode (see [`Mode::from_raw`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FileType {
+    /// A regular file (`-`).
+    Regular,
+    /// A directory (`d`).
+    Directory,
+    /// A symbolic link (`l`).
+    Symlink,
+    /// A character device (`c`).
+    CharDevice,
+    /// A block device (`b`).
+    BlockDevice,
+    /// A named pipe (`p`).
+    Fifo,
+    /// A socket (`s`).
+    Socket,
+}
+
+/// Style of the icons produced by [`Mode::as_icons`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IconStyle {
+    /// Granted permissions as `R`, `W`, and `X`, and denied permissions as `·`.
+    Dots,
+    /// Granted permissions as `R`, `W`, and `X`, and denied permissions as `r`, `w`, and `x`.
+    Case,
+    /// Granted permissions as 🔓 and denied permissions as 🔒.
+    Locks,
+}
+
+/// A common misconfiguration of a [`Mode`], as reported by [`Mode::lint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModeWarning {
+    /// The other class has write permission.
+    WorldWritable,
+    /// The group class has write permission, without the sticky bit to protect entries of a
+    /// directory from deletion by other group members.
+    GroupWritableNoSticky,
+    /// Some class has execute permission but not read permission.
+    ExecutableButNotReadable,
+    /// The setuid bit is set, and the group or other class has write permission.
+    SetuidWithWrite,
+}
+
 /// Error encountered when parsing a string into a [`Mode`] or [`Perm`].
-#[derive(Debug, PartialEq, Error)]
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "std", derive(Error))]
 pub enum ParseError {
     /// An unexpected character was encountered while parsing (e.g. an 'r' when an 'x' or '-' was
     /// expected, or any character after EOI was expected).
-    #[error("invalid character encountered")]
     UnexpectedChar {
         /// The position (zero-indexed) of the unexpected character.
         pos: usize,
@@ -44,22 +234,168 @@ pub enum ParseError {
         expected: Option<Vec<char>>,
     },
     /// End-of-input was encountered when more input was expected.
-    #[error("unexepected end-of-input")]
     UnexpectedEoi {
         /// The position (zero-indexed) where another character was expected.
         pos: usize,
     },
+    /// A numeric input was too short to be a [`Mode`] (see [`Mode::parse`]).
+    ShortNum {
+        /// The number of digits in the input.
+        len: usize,
+        /// Flag indicating whether the input is a valid [`Perm`], in which case a [`Perm`] may
+        /// have been intended.
+        perm: bool,
+    },
+    /// A key was not recognized (see [`Mode::from_kv`]).
+    InvalidKey {
+        /// The key encountered.
+        key: String,
+    },
+    /// A numeric value was not a valid octal digit (see [`Mode::from_u32_array`]).
+    OutOfRange {
+        /// The index of the value.
+        index: usize,
+        /// The value encountered.
+        value: u32,
+    },
+    /// The input was not valid UTF-8 (see [`Mode::from_os_str`]).
+    NonUtf8,
+    /// The input was not of the required length (see [`Mode::from_sym_strict`]).
+    InvalidLength {
+        /// The number of characters in the input.
+        len: usize,
+        /// The number of characters required.
+        expected: usize,
+    },
+}
+
+impl ParseError {
+    /// Get the position (zero-indexed) in the input at which the error occurred. Errors that are
+    /// not tied to a position, such as [`ParseError::InvalidKey`], occur at position 0,
+    /// [`ParseError::OutOfRange`] occurs at the index of the value, and
+    /// [`ParseError::InvalidLength`] occurs at the end of the input or at the first extra
+    /// character.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// assert_eq!(4, Mode::from_sym("rwxrx").unwrap_err().pos());
+    /// assert_eq!(2, Mode::from_num("75").unwrap_err().pos());
+    /// ```
+    #[inline]
+    pub const fn pos(&self) -> usize {
+        match self {
+            Self::UnexpectedChar { pos, .. } | Self::UnexpectedEoi { pos } => *pos,
+            Self::ShortNum { len, .. } => *len,
+            Self::InvalidKey { .. } | Self::NonUtf8 => 0,
+            Self::OutOfRange { index, .. } => *index,
+            Self::InvalidLength { len, expected } if *len < *expected => *len,
+            Self::InvalidLength { expected, .. } => *expected,
+        }
+    }
+
+    /// Render the error beneath the input that produced it, with a caret marking the position at
+    /// which the error occurred.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// let input = "rwxr-zr-x";
+    /// let err = Mode::from_sym(input).unwrap_err();
+    ///
+    /// assert_eq!(
+    ///     "rwxr-zr-x\n     ^ unexpected 'z', expected one of 'x', 's', 'S', '-'",
+    ///     err.render_with_input(input)
+    /// );
+    /// ```
+    pub fn render_with_input(&self, input: &str) -> String {
+        let detail = match self {
+            Self::UnexpectedChar {
+                c,
+                expected: Some(expected),
+                ..
+            } => {
+                let expected: Vec<String> = expected.iter().map(|e| format!("'{}'", e)).collect();
+                match expected.len() {
+                    1 => format!("unexpected '{}', expected {}", c, expected[0]),
+                    _ => format!(
+                        "unexpected '{}', expected one of {}",
+                        c,
+                        expected.join(", ")
+                    ),
+                }
+            }
+            Self::UnexpectedChar {
+                c, expected: None, ..
+            } => format!("unexpected '{}', expected end-of-input", c),
+            err => err.to_string(),
+        };
+
+        format!("{}\n{}^ {}", input, " ".repeat(self.pos()), detail)
+    }
+}
+
+// The implementation is written out, rather than derived with thiserror, so that it is available
+// without the standard library.
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedChar { .. } => f.write_str("invalid character encountered"),
+            Self::UnexpectedEoi { .. } => f.write_str("unexepected end-of-input"),
+            Self::ShortNum { perm: true, .. } => {
+                f.write_str("expected 3 digits for a mode, found a single permission digit")
+            }
+            Self::ShortNum { len, .. } => {
+                write!(f, "expected 3 digits for a mode, found {}", len)
+            }
+            Self::InvalidKey { key } => write!(f, "unknown key '{}'", key),
+            Self::OutOfRange { index, value } => {
+                write!(f, "value {} at index {} is out of range 0-7", value, index)
+            }
+            Self::NonUtf8 => f.write_str("input is not valid UTF-8"),
+            Self::InvalidLength { len, expected } => {
+                write!(f, "expected {} characters, found {}", expected, len)
+            }
+        }
+    }
 }
 
+// Tests link the standard library even without the std feature, and return ParseError as a
+// boxed error.
+#[cfg(all(test, not(feature = "std")))]
+impl std::error::Error for ParseError {}
+
 impl Mode {
-    /// Create a new [`Mode`].
+    /// Length of the octal representation of a [`Mode`] (see [`Mode::as_num`]).
+    pub const NUM_LEN: usize = 3;
+    /// Length of the octal representation of a [`Mode`] with any of the setuid, setgid, or sticky
+    /// bits set (see [`Mode::as_num`]).
+    pub const NUM_SPECIAL_LEN: usize = 4;
+    /// Length of the symbolic representation of a [`Mode`] (see [`Mode::as_sym`]).
+    pub const SYM_LEN: usize = 9;
+
+    /// Create a new [`Mode`], without the setuid, setgid, or sticky bits set.
     #[inline]
     pub const fn new(user: Perm, group: Perm, other: Perm) -> Self {
-        Self { user, group, other }
+        Self {
+            user,
+            group,
+            other,
+            setuid: false,
+            setgid: false,
+            sticky: false,
+        }
     }
 
     /// Get the octal representation the [`Mode`].
     ///
+    /// If any of the setuid, setgid, or sticky bits are set, they are included as a leading
+    /// fourth digit. Otherwise, the representation has three digits.
+    ///
     /// # Examples
     ///
     /// ```
@@ -70,19 +406,119 @@ impl Mode {
     ///                   Perm::new(true, false, true));
     ///
     /// assert_eq!("755", m.as_num());
+    /// assert_eq!("4755", Mode::from_num("4755").unwrap().as_num());
     /// ```
     #[inline]
     pub fn as_num(&self) -> String {
-        format!(
+        let perms = format!(
             "{}{}{}",
             self.user.as_num(),
             self.group.as_num(),
             self.other.as_num()
-        )
+        );
+        match self.special_bits() {
+            0 => perms,
+            special => format!("{}{}", special, perms),
+        }
+    }
+
+    /// Get the argument to `install -m` that sets the [`Mode`]. This is the octal form, which is
+    /// three digits long, or four if any of the setuid, setgid, or sticky bits are set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// assert_eq!("755", Mode::from_num("755").unwrap().to_install_arg());
+    /// assert_eq!("044", Mode::from_sym("---r--r--").unwrap().to_install_arg());
+    /// assert_eq!("4755", Mode::from_num("4755").unwrap().to_install_arg());
+    /// ```
+    #[inline]
+    pub fn to_install_arg(&self) -> String {
+        self.as_num()
+    }
+
+    /// Get the symbolic representation of the [`Mode`] (see [`Mode::as_sym`]) prefixed by the
+    /// character of a [`FileType`], as in the output of `ls -l`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::{FileType, Mode};
+    ///
+    /// let (mode, file_type) = Mode::from_raw(0o41777);
+    /// assert_eq!("drwxrwxrwt", mode.as_sym_with_type(file_type.unwrap()));
+    /// ```
+    #[inline]
+    pub fn as_sym_with_type(&self, file_type: FileType) -> String {
+        format!("{}{}", file_type.as_char(), self.as_sym_static())
+    }
+
+    /// Get a representation of the [`Mode`] for display in user interfaces, with a group of
+    /// three icons for each class in the given [`IconStyle`]. The setuid, setgid, and sticky
+    /// bits are not shown.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::{IconStyle, Mode};
+    ///
+    /// let mode = Mode::from_num("750").unwrap();
+    /// assert_eq!("RWX R·X ···", mode.as_icons(IconStyle::Dots));
+    /// assert_eq!("RWX RwX rwx", mode.as_icons(IconStyle::Case));
+    /// assert_eq!("🔓🔓🔓 🔓🔒🔓 🔒🔒🔒", mode.as_icons(IconStyle::Locks));
+    /// ```
+    pub fn as_icons(&self, style: IconStyle) -> String {
+        let icon = |granted: bool, c: char| match (style, granted) {
+            (IconStyle::Dots, false) => '·',
+            (IconStyle::Case, false) => c,
+            (IconStyle::Dots, true) | (IconStyle::Case, true) => c.to_ascii_uppercase(),
+            (IconStyle::Locks, false) => '🔒',
+            (IconStyle::Locks, true) => '🔓',
+        };
+
+        [&self.user, &self.group, &self.other]
+            .iter()
+            .map(|perm| {
+                [
+                    icon(perm.read, 'r'),
+                    icon(perm.write, 'w'),
+                    icon(perm.execute, 'x'),
+                ]
+                .iter()
+                .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Get the octal representation of the [`Mode`] as a buffer of three ASCII digits. The
+    /// setuid, setgid, and sticky bits are not included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// let m = Mode::from_num("755").unwrap();
+    /// assert_eq!(b"755", &m.write_num_ascii());
+    /// ```
+    #[inline]
+    pub const fn write_num_ascii(&self) -> [u8; Mode::NUM_LEN] {
+        [
+            b'0' + self.user.bits(),
+            b'0' + self.group.bits(),
+            b'0' + self.other.bits(),
+        ]
     }
 
     /// Get the symbolic representation the [`Mode`].
     ///
+    /// The setuid and setgid bits are shown in the execute position of the user and group classes
+    /// as `s`, or `S` if the class lacks execute permission. The sticky bit is shown in the execute
+    /// position of the other class as `t`, or `T`.
+    ///
     /// # Examples
     ///
     /// ```
@@ -93,19 +529,54 @@ impl Mode {
     ///                   Perm::new(true, false, true));
     ///
     /// assert_eq!("rwxr-xr-x", m.as_sym());
+    /// assert_eq!("rwsr-xr-x", Mode::from_num("4755").unwrap().as_sym());
+    /// assert_eq!("rwSr--r--", Mode::from_num("4644").unwrap().as_sym());
+    /// assert_eq!("rwxrwxrwt", Mode::from_num("1777").unwrap().as_sym());
     /// ```
     #[inline]
     pub fn as_sym(&self) -> String {
-        format!(
-            "{}{}{}",
-            self.user.as_sym_full(),
-            self.group.as_sym_full(),
-            self.other.as_sym_full()
-        )
+        let mut sym = String::with_capacity(Self::SYM_LEN);
+        Self::push_class_sym(&mut sym, &self.user, self.setuid, 's');
+        Self::push_class_sym(&mut sym, &self.group, self.setgid, 's');
+        Self::push_class_sym(&mut sym, &self.other, self.sticky, 't');
+        sym
+    }
+
+    /// Get the octal representation of the [`Mode`] as a static string, without allocating.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// let m = Mode::from_sym("rwxr-xr-x").unwrap();
+    /// assert_eq!("755", m.as_num_static());
+    /// ```
+    #[inline]
+    pub const fn as_num_static(&self) -> &'static str {
+        tables::MODE_NUM[self.bits() as usize]
+    }
+
+    /// Get the symbolic representation of the [`Mode`] as a static string, without allocating.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// let m = Mode::from_num("755").unwrap();
+    /// assert_eq!("rwxr-xr-x", m.as_sym_static());
+    /// ```
+    #[inline]
+    pub const fn as_sym_static(&self) -> &'static str {
+        tables::MODE_SYM[self.bits() as usize]
     }
 
     /// Create a [`Mode`] from its octal form, returning [`ParseError`] if the input is invalid.
     ///
+    /// The input may have three digits, or four digits with the first giving the setuid (4),
+    /// setgid (2), and sticky (1) bits.
+    ///
     /// # Examples
     ///
     /// ```
@@ -118,301 +589,318 @@ impl Mode {
     ///     Mode::from_num("755").unwrap()
     /// );
     ///
+    /// let m = Mode::from_num("2755").unwrap();
+    /// assert!(!m.setuid && m.setgid && !m.sticky);
+    /// assert_eq!(Mode::from_num("755").unwrap().group, m.group);
+    ///
+    /// let m = Mode::from_num("4644").unwrap();
+    /// assert!(m.setuid && !m.setgid && !m.sticky);
+    /// assert_eq!(Mode::from_num("644").unwrap().user, m.user);
+    ///
     /// assert_eq!(
-    ///     ParseError::UnexpectedChar { pos: 3, c: '8', expected: None },
+    ///     ParseError::UnexpectedChar {
+    ///         pos: 3,
+    ///         c: '8',
+    ///         expected: Some(vec!['0', '1', '2', '3', '4', '5', '6', '7'])
+    ///     },
     ///     Mode::from_num("6008").unwrap_err()
     /// );
+    /// assert_eq!(
+    ///     ParseError::UnexpectedChar { pos: 4, c: '5', expected: None },
+    ///     Mode::from_num("47555").unwrap_err()
+    /// );
     /// ```
     #[inline]
     pub fn from_num(num: &str) -> Result<Self, ParseError> {
         #[inline]
-        fn next_val(pos: &mut usize, chars: &mut Chars) -> Result<Perm, ParseError> {
-            let c = chars
-                .next()
-                .ok_or_else(|| ParseError::UnexpectedEoi { pos: *pos })?;
-            *pos += 1;
-            Perm::from_num(&c.to_string()).map_err(|err| match err {
-                ParseError::UnexpectedChar {
-                    c,
-                    pos: p,
-                    expected,
-                } => ParseError::UnexpectedChar {
-                    c,
-                    pos: p + *pos,
-                    expected,
-                },
-                ParseError::UnexpectedEoi { pos: p } => ParseError::UnexpectedEoi { pos: p + *pos },
-            })
+        fn digit(num: &str, pos: usize) -> Result<u16, ParseError> {
+            let b = *num
+                .as_bytes()
+                .get(pos)
+                .ok_or(ParseError::UnexpectedEoi { pos })?;
+            // All preceding bytes are ASCII digits, so pos is a char boundary.
+            match b {
+                b'0'..=b'7' => Ok((b - b'0') as u16),
+                _ => Err(ParseError::UnexpectedChar {
+                    c: num[pos..].chars().next().unwrap(),
+                    pos,
+                    expected: Some(octal_digits()),
+                }),
+            }
         }
 
-        let mut chars = num.chars();
-        let mut pos = 0;
-        let user = next_val(&mut pos, &mut chars)?;
-        let group = next_val(&mut pos, &mut chars)?;
-        let other = next_val(&mut pos, &mut chars)?;
+        let len = if num.len() > Self::NUM_LEN {
+            Self::NUM_SPECIAL_LEN
+        } else {
+            Self::NUM_LEN
+        };
 
-        if let Some(c) = chars.next() {
+        let mut bits = 0;
+        for pos in 0..len {
+            bits = bits << 3 | digit(num, pos)?;
+        }
+
+        if let Some(c) = num[len..].chars().next() {
             Err(ParseError::UnexpectedChar {
-                pos,
+                pos: len,
                 c,
                 expected: None,
             })
         } else {
-            Ok(Self { user, group, other })
+            Ok(Self::from_bits(bits))
         }
     }
 
-    /// Create a [`Mode`] from its symbolic form, returning [`ParseError`] if the input is invalid.
+    /// Create a [`Mode`] from its octal digits separated by commas (e.g. `7,5,5`), returning
+    /// [`ParseError`] if the input is invalid. Error positions include the commas.
     ///
     /// # Examples
     ///
     /// ```
-    /// use cchmod::{Mode, Perm, ParseError};
+    /// use cchmod::{Mode, ParseError};
+    ///
+    /// assert_eq!(Mode::from_num("755").unwrap(), Mode::from_num_csv("7,5,5").unwrap());
     ///
     /// assert_eq!(
-    ///     Mode::new(Perm::new(true, true, true),
-    ///               Perm::new(true, false, true),
-    ///               Perm::new(true, false, true)),
-    ///     Mode::from_sym("rwxr-xr-x").unwrap()
+    ///     ParseError::UnexpectedChar {
+    ///         pos: 2,
+    ///         c: '8',
+    ///         expected: Some(vec!['0', '1', '2', '3', '4', '5', '6', '7'])
+    ///     },
+    ///     Mode::from_num_csv("7,8,5").unwrap_err()
     /// );
-    ///
     /// assert_eq!(
-    ///     ParseError::UnexpectedEoi { pos: 6 },
-    ///     Mode::from_sym("rwxr-x").unwrap_err()
+    ///     ParseError::UnexpectedChar { pos: 1, c: '5', expected: Some(vec![',']) },
+    ///     Mode::from_num_csv("755").unwrap_err()
     /// );
     /// assert_eq!(
-    ///     ParseError::UnexpectedChar { pos: 9, c: 'r', expected: None },
-    ///     Mode::from_sym("rwxr-xr-xr").unwrap_err()
+    ///     ParseError::UnexpectedEoi { pos: 4 },
+    ///     Mode::from_num_csv("7,5,").unwrap_err()
     /// );
     /// ```
-    #[inline]
-    pub fn from_sym(sym: &str) -> Result<Self, ParseError> {
+    pub fn from_num_csv(csv: &str) -> Result<Self, ParseError> {
+        #[inline]
+        fn digit(pos: usize, c: char) -> Result<Perm, ParseError> {
+            Perm::from_octal_digit(c).ok_or_else(|| ParseError::UnexpectedChar {
+                pos,
+                c,
+                expected: Some(octal_digits()),
+            })
+        }
+
         #[inline]
-        fn shift_err(diff: usize) -> impl Fn(ParseError) -> ParseError {
-            move |err: ParseError| match err {
-                ParseError::UnexpectedEoi { pos } => ParseError::UnexpectedEoi { pos: pos + diff },
-                ParseError::UnexpectedChar { c, pos, expected } => ParseError::UnexpectedChar {
+        fn comma(pos: usize, c: char) -> Result<(), ParseError> {
+            if c == ',' {
+                Ok(())
+            } else {
+                Err(ParseError::UnexpectedChar {
+                    pos,
                     c,
-                    pos: pos + diff,
-                    expected,
-                },
+                    expected: Some(vec![',']),
+                })
             }
         }
 
-        let user = Perm::from_sym_full(&sym.chars().take(3).collect::<String>())?;
-        let group = Perm::from_sym_full(&sym.chars().skip(3).take(3).collect::<String>())
-            .map_err(shift_err(3))?;
-        let other = Perm::from_sym_full(&sym.chars().skip(6).take(3).collect::<String>())
-            .map_err(shift_err(6))?;
+        let mut chars = csv.chars();
+        let mut next = |pos: usize| chars.next().ok_or(ParseError::UnexpectedEoi { pos });
 
-        if let Some(c) = sym.chars().nth(9) {
+        let user = digit(0, next(0)?)?;
+        comma(1, next(1)?)?;
+        let group = digit(2, next(2)?)?;
+        comma(3, next(3)?)?;
+        let other = digit(4, next(4)?)?;
+
+        if let Some(c) = chars.next() {
             Err(ParseError::UnexpectedChar {
+                pos: 5,
                 c,
-                pos: 9,
                 expected: None,
             })
         } else {
-            Ok(Self { user, group, other })
+            Ok(Self::new(user, group, other))
         }
     }
 
-    /// Compute the diff ([`ModeDiff`]) between two modes.
+    /// Get the permissions of the [`Mode`] as nine comma-separated booleans, in the order user
+    /// read, write, execute, then group, then other (e.g.
+    /// `true,true,true,true,false,true,true,false,true` for `755`). Special bits are not included.
     ///
     /// # Examples
     ///
     /// ```
-    /// use cchmod::{Mode, ModeDiff, PermDiff, DiffOp::*};
-    ///
-    /// let a = Mode::from_num("777").unwrap();
-    /// let b = Mode::from_num("644").unwrap();
+    /// use cchmod::Mode;
     ///
     /// assert_eq!(
-    ///     ModeDiff {
-    ///         user: PermDiff { read: Same, write: Same, execute: Minus },
-    ///         group: PermDiff { read: Same, write: Minus, execute: Minus },
-    ///         other: PermDiff { read: Same, write: Minus, execute: Minus },
-    ///     },
-    ///     a.diff(&b)
+    ///     "true,true,false,true,false,false,false,false,false",
+    ///     Mode::from_num("640").unwrap().to_bool_csv()
     /// );
     /// ```
-    #[inline]
-    pub const fn diff(&self, other: &Self) -> ModeDiff {
-        ModeDiff {
-            user: self.user.diff(&other.user),
-            group: self.group.diff(&other.group),
-            other: self.other.diff(&other.other),
-        }
-    }
-}
-
-impl Perm {
-    /// Create a new [`Perm`].
-    #[inline]
-    pub const fn new(read: bool, write: bool, execute: bool) -> Self {
-        Self {
-            read,
-            write,
-            execute,
-        }
+    pub fn to_bool_csv(&self) -> String {
+        let bits = self.bits();
+        (0..9)
+            .rev()
+            .map(|i| if bits >> i & 1 != 0 { "true" } else { "false" })
+            .collect::<Vec<_>>()
+            .join(",")
     }
 
-    /// Get the octal representation the [`Perm`].
+    /// Create a [`Mode`] from nine comma-separated booleans (see [`Mode::to_bool_csv`]),
+    /// returning [`ParseError`] if the input is invalid. Error positions include the commas.
     ///
     /// # Examples
     ///
     /// ```
-    /// use cchmod::Perm;
+    /// use cchmod::{Mode, ParseError};
     ///
-    /// let p1 = Perm::new(true, true, false);
-    /// assert_eq!("6", p1.as_num());
+    /// let csv = "true,true,true,true,false,true,true,false,true";
+    /// assert_eq!(Mode::from_num("755").unwrap(), Mode::from_bool_csv(csv).unwrap());
     ///
-    /// let p2 = Perm::new(true, false, true);
-    /// assert_eq!("5", p2.as_num());
+    /// assert_eq!(
+    ///     ParseError::UnexpectedChar { pos: 5, c: 'y', expected: Some(vec!['t', 'f']) },
+    ///     Mode::from_bool_csv("true,yes").unwrap_err()
+    /// );
+    /// assert_eq!(
+    ///     ParseError::UnexpectedEoi { pos: 10 },
+    ///     Mode::from_bool_csv("true,false").unwrap_err()
+    /// );
     /// ```
-    #[inline]
-    pub fn as_num(&self) -> String {
-        ((if self.read { 4 } else { 0 })
-            + (if self.write { 2 } else { 0 })
-            + (if self.execute { 1 } else { 0 }))
-        .to_string()
-    }
+    pub fn from_bool_csv(csv: &str) -> Result<Self, ParseError> {
+        let mut chars = csv.chars();
+        let mut pos = 0;
+        let mut expect = |options: &[char]| match chars.next() {
+            Some(c) if options.contains(&c) => {
+                pos += 1;
+                Ok(c)
+            }
+            Some(c) => Err(ParseError::UnexpectedChar {
+                pos,
+                c,
+                expected: Some(options.to_vec()),
+            }),
+            None => Err(ParseError::UnexpectedEoi { pos }),
+        };
 
-    /// Get the symbolic representation, with ungranted permissions omitted, of the [`Perm`].
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// use cchmod::Perm;
-    ///
-    /// let p1 = Perm::new(true, true, false);
-    /// assert_eq!("rw", p1.as_sym());
-    ///
-    /// let p2 = Perm::new(true, false, true);
-    /// assert_eq!("rx", p2.as_sym());
-    /// ```
-    #[inline]
-    pub fn as_sym(&self) -> String {
-        let r = if self.read { "r" } else { "" };
-        let w = if self.write { "w" } else { "" };
-        let x = if self.execute { "x" } else { "" };
-        format!("{}{}{}", r, w, x)
+        let mut bits = 0;
+        for i in 0..9 {
+            if i > 0 {
+                expect(&[','])?;
+            }
+
+            let (value, rest) = match expect(&['t', 'f'])? {
+                't' => (true, "rue"),
+                _ => (false, "alse"),
+            };
+            for c in rest.chars() {
+                expect(&[c])?;
+            }
+            bits = bits << 1 | value as u16;
+        }
+
+        match chars.next() {
+            Some(c) => Err(ParseError::UnexpectedChar {
+                pos,
+                c,
+                expected: None,
+            }),
+            None => Ok(Self::from_bits(bits)),
+        }
     }
 
-    /// Get the symbolic representation, with ungranted permissions as '-', of the [`Perm`].
+    /// Create a [`Mode`] from a buffer of three ASCII octal digits, returning [`ParseError`] if
+    /// the input is invalid. Invalid bytes are reported as their corresponding `char`, and the
+    /// setuid, setgid, and sticky bits are not set.
     ///
     /// # Examples
     ///
     /// ```
-    /// use cchmod::Perm;
+    /// use cchmod::{Mode, ParseError};
     ///
-    /// let p1 = Perm::new(true, true, false);
-    /// assert_eq!("rw-", p1.as_sym_full());
+    /// assert_eq!(Mode::from_num("755").unwrap(), Mode::from_num_ascii(b"755").unwrap());
     ///
-    /// let p2 = Perm::new(true, false, true);
-    /// assert_eq!("r-x", p2.as_sym_full());
+    /// assert_eq!(
+    ///     ParseError::UnexpectedChar {
+    ///         pos: 1,
+    ///         c: '9',
+    ///         expected: Some(vec!['0', '1', '2', '3', '4', '5', '6', '7'])
+    ///     },
+    ///     Mode::from_num_ascii(b"795").unwrap_err()
+    /// );
     /// ```
     #[inline]
-    pub fn as_sym_full(&self) -> String {
-        let r = if self.read { "r" } else { "-" };
-        let w = if self.write { "w" } else { "-" };
-        let x = if self.execute { "x" } else { "-" };
-        format!("{}{}{}", r, w, x)
+    pub fn from_num_ascii(buf: &[u8; Mode::NUM_LEN]) -> Result<Self, ParseError> {
+        Self::from_num_chars(buf[0] as char, buf[1] as char, buf[2] as char)
     }
 
-    /// Create a [`Perm`] from its octal form, returning [`ParseError`] if the input is invalid.
+    /// Create a [`Mode`] from the octal digits of each class, returning [`ParseError`] if any of
+    /// them is invalid. Errors are reported at positions 0, 1, and 2 for `u`, `g`, and `o`. The
+    /// setuid, setgid, and sticky bits are not set.
     ///
     /// # Examples
     ///
     /// ```
-    /// use cchmod::{Perm, ParseError};
+    /// use cchmod::{Mode, ParseError};
     ///
-    /// assert_eq!(Perm::new(true, true, true), Perm::from_num("7").unwrap());
-    /// assert_eq!(Perm::new(true, false, false), Perm::from_num("4").unwrap());
+    /// assert_eq!(Mode::from_num("755").unwrap(), Mode::from_num_chars('7', '5', '5').unwrap());
     ///
     /// assert_eq!(
-    ///     ParseError::UnexpectedEoi { pos: 0 },
-    ///     Perm::from_num("").unwrap_err()
-    /// );
-    /// assert_eq!(
     ///     ParseError::UnexpectedChar {
-    ///         pos: 0,
-    ///         c: '8',
+    ///         pos: 2,
+    ///         c: 'x',
     ///         expected: Some(vec!['0', '1', '2', '3', '4', '5', '6', '7'])
     ///     },
-    ///     Perm::from_num("8").unwrap_err()
+    ///     Mode::from_num_chars('7', '5', 'x').unwrap_err()
     /// );
     /// ```
     #[inline]
-    pub fn from_num(num: &str) -> Result<Self, ParseError> {
-        let tup = match num {
-            "7" => (true, true, true),
-            "6" => (true, true, false),
-            "5" => (true, false, true),
-            "4" => (true, false, false),
-            "3" => (false, true, true),
-            "2" => (false, true, false),
-            "1" => (false, false, true),
-            "0" => (false, false, false),
-            "" => return Err(ParseError::UnexpectedEoi { pos: 0 }),
-            c => {
-                return Err(ParseError::UnexpectedChar {
-                    c: c.chars().next().unwrap(),
-                    pos: 0,
-                    expected: Some(
-                        (0..=7)
-                            .map(|n| std::char::from_digit(n, 10).unwrap())
-                            .collect(),
-                    ),
-                })
-            }
-        };
+    pub fn from_num_chars(u: char, g: char, o: char) -> Result<Self, ParseError> {
+        #[inline]
+        fn digit(pos: usize, c: char) -> Result<Perm, ParseError> {
+            Perm::from_octal_digit(c).ok_or_else(|| ParseError::UnexpectedChar {
+                pos,
+                c,
+                expected: Some(octal_digits()),
+            })
+        }
 
-        Ok(tup.into())
+        Ok(Self::new(digit(0, u)?, digit(1, g)?, digit(2, o)?))
     }
 
-    /// Create a [`Perm`] from its symbolic form, returning [`ParseError`] if the input is invalid.
+    /// Create a [`Mode`] from its symbolic form, returning [`ParseError`] if the input is invalid.
+    ///
+    /// The setuid, setgid, and sticky bits are read from the execute positions, as written by
+    /// [`Mode::as_sym`].
     ///
     /// # Examples
     ///
     /// ```
-    /// use cchmod::{Perm, ParseError};
+    /// use cchmod::{Mode, Perm, ParseError};
     ///
-    /// assert_eq!(Perm::new(true, true, true), Perm::from_sym_full("rwx").unwrap());
-    /// assert_eq!(Perm::new(true, false, false), Perm::from_sym_full("r--").unwrap());
+    /// assert_eq!(
+    ///     Mode::new(Perm::new(true, true, true),
+    ///               Perm::new(true, false, true),
+    ///               Perm::new(true, false, true)),
+    ///     Mode::from_sym("rwxr-xr-x").unwrap()
+    /// );
+    /// assert_eq!(Mode::from_num("4644").unwrap(), Mode::from_sym("rwSr--r--").unwrap());
+    /// assert_eq!(Mode::from_num("3775").unwrap(), Mode::from_sym("rwxrwsr-t").unwrap());
     ///
     /// assert_eq!(
-    ///     ParseError::UnexpectedEoi { pos: 2 },
-    ///     Perm::from_sym_full("rw").unwrap_err()
+    ///     ParseError::UnexpectedEoi { pos: 6 },
+    ///     Mode::from_sym("rwxr-x").unwrap_err()
     /// );
     /// assert_eq!(
-    ///     ParseError::UnexpectedChar { pos: 3, c: 'r', expected: None },
-    ///     Perm::from_sym_full("rwxr").unwrap_err()
+    ///     ParseError::UnexpectedChar { pos: 9, c: 'r', expected: None },
+    ///     Mode::from_sym("rwxr-xr-xr").unwrap_err()
     /// );
     /// ```
     #[inline]
-    pub fn from_sym_full(sym: &str) -> Result<Self, ParseError> {
-        #[inline]
-        fn process_char(e: char, pos: &mut usize, chars: &mut Chars) -> Result<bool, ParseError> {
-            let r = match chars.next() {
-                None => Err(ParseError::UnexpectedEoi { pos: *pos }),
-                Some(c) if c == e => Ok(true),
-                Some('-') => Ok(false),
-                Some(c) => Err(ParseError::UnexpectedChar {
-                    c,
-                    pos: *pos,
-                    expected: Some(vec![e, '-']),
-                }),
-            };
-            *pos += 1;
-            r
-        }
-
+    pub fn from_sym(sym: &str) -> Result<Self, ParseError> {
         let mut chars = sym.chars();
-
         let mut pos = 0;
-        let read = process_char('r', &mut pos, &mut chars)?;
-        let write = process_char('w', &mut pos, &mut chars)?;
-        let execute = process_char('x', &mut pos, &mut chars)?;
+
+        let (user, setuid) = Self::class_from_sym(&mut chars, &mut pos, 's')?;
+        let (group, setgid) = Self::class_from_sym(&mut chars, &mut pos, 's')?;
+        let (other, sticky) = Self::class_from_sym(&mut chars, &mut pos, 't')?;
 
         if let Some(c) = chars.next() {
             Err(ParseError::UnexpectedChar {
@@ -422,221 +910,4718 @@ impl Perm {
             })
         } else {
             Ok(Self {
-                read,
-                write,
-                execute,
+                user,
+                group,
+                other,
+                setuid,
+                setgid,
+                sticky,
             })
         }
     }
 
-    /// Compute the diff ([`PermDiff`]) between two [`Perm`]s.
+    /// Create a [`Mode`] from its full nine-character symbolic form (see [`Mode::from_sym`]),
+    /// returning [`ParseError::InvalidLength`] if the input is of any other length, or another
+    /// [`ParseError`] if it is otherwise invalid.
     ///
     /// # Examples
     ///
     /// ```
-    /// use cchmod::{Perm, PermDiff, DiffOp::*};
+    /// use cchmod::{Mode, ParseError};
     ///
-    /// let a = Perm::from_num("7").unwrap();
-    /// let b = Perm::from_num("6").unwrap();
+    /// assert_eq!(Mode::from_num("755").unwrap(), Mode::from_sym_strict("rwxr-xr-x").unwrap());
     ///
     /// assert_eq!(
-    ///     PermDiff { read: Same, write: Same, execute: Minus },
-    ///     a.diff(&b)
+    ///     ParseError::InvalidLength { len: 3, expected: 9 },
+    ///     Mode::from_sym_strict("rwx").unwrap_err()
+    /// );
+    /// assert_eq!(
+    ///     ParseError::InvalidLength { len: 10, expected: 9 },
+    ///     Mode::from_sym_strict("rwxr-xr-xr").unwrap_err()
     /// );
     /// ```
-    #[inline]
-    pub const fn diff(&self, other: &Self) -> PermDiff {
-        PermDiff {
-            read: bool_diff(self.read, other.read),
-            write: bool_diff(self.write, other.write),
-            execute: bool_diff(self.execute, other.execute),
+    pub fn from_sym_strict(sym: &str) -> Result<Self, ParseError> {
+        match sym.chars().count() {
+            Self::SYM_LEN => Self::from_sym(sym),
+            len => Err(ParseError::InvalidLength {
+                len,
+                expected: Self::SYM_LEN,
+            }),
         }
     }
-}
 
-impl From<(bool, bool, bool)> for Perm {
-    /// Create a [`Perm`] from a tuple of boolean with form `(user, group, other)`.
-    #[inline]
-    fn from(tup: (bool, bool, bool)) -> Self {
-        Self {
-            read: tup.0,
-            write: tup.1,
-            execute: tup.2,
-        }
+    /// Create a [`Mode`] from its symbolic form followed by an optional [`AclIndicator`], as
+    /// printed by `ls`, returning [`ParseError`] if the input is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::{AclIndicator, Mode, ParseError};
+    ///
+    /// let mode = Mode::from_num("755").unwrap();
+    /// assert_eq!((mode, None), Mode::from_sym_with_acl("rwxr-xr-x").unwrap());
+    /// assert_eq!(
+    ///     (mode, Some(AclIndicator::Acl)),
+    ///     Mode::from_sym_with_acl("rwxr-xr-x+").unwrap()
+    /// );
+    /// assert_eq!(
+    ///     (mode, Some(AclIndicator::SecurityContext)),
+    ///     Mode::from_sym_with_acl("rwxr-xr-x.").unwrap()
+    /// );
+    ///
+    /// assert_eq!(
+    ///     ParseError::UnexpectedChar { pos: 9, c: '@', expected: Some(vec!['+', '.']) },
+    ///     Mode::from_sym_with_acl("rwxr-xr-x@").unwrap_err()
+    /// );
+    /// ```
+    pub fn from_sym_with_acl(sym: &str) -> Result<(Self, Option<AclIndicator>), ParseError> {
+        Self::from_sym_with_acl_chars(&mut sym.chars(), 0)
     }
-}
 
-/// The diff between two Modes.
-#[derive(Debug, PartialEq)]
-pub struct ModeDiff {
-    pub user: PermDiff,
-    pub group: PermDiff,
-    pub other: PermDiff,
-}
-
-/// The diff between two Perms.
-#[derive(Debug, PartialEq)]
-pub struct PermDiff {
-    pub read: DiffOp,
-    pub write: DiffOp,
-    pub execute: DiffOp,
-}
+    /// Create a [`Mode`] from the first column of `ls -l` output, which begins with a file type
+    /// character (e.g. `-` or `d`) and may end with an [`AclIndicator`], returning [`ParseError`]
+    /// if the input is invalid. The file type is discarded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::{AclIndicator, Mode, ParseError};
+    ///
+    /// assert_eq!(
+    ///     (Mode::from_num("644").unwrap(), None),
+    ///     Mode::from_ls("-rw-r--r--").unwrap()
+    /// );
+    /// assert_eq!(
+    ///     (Mode::from_num("1777").unwrap(), Some(AclIndicator::Acl)),
+    ///     Mode::from_ls("drwxrwxrwt+").unwrap()
+    /// );
+    ///
+    /// assert_eq!(
+    ///     ParseError::UnexpectedEoi { pos: 9 },
+    ///     Mode::from_ls("-rwxr-xr-").unwrap_err()
+    /// );
+    /// ```
+    pub fn from_ls(ls: &str) -> Result<(Self, Option<AclIndicator>), ParseError> {
+        const FILE_TYPES: [char; 7] = ['-', 'b', 'c', 'd', 'l', 'p', 's'];
 
-/// Enum for diff between two values.
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
-pub enum DiffOp {
-    Plus,
-    Same,
-    Minus,
-}
+        let mut chars = ls.chars();
+        match chars.next() {
+            Some(c) if FILE_TYPES.contains(&c) => Self::from_sym_with_acl_chars(&mut chars, 1),
+            Some(c) => Err(ParseError::UnexpectedChar {
+                pos: 0,
+                c,
+                expected: Some(FILE_TYPES.to_vec()),
+            }),
+            None => Err(ParseError::UnexpectedEoi { pos: 0 }),
+        }
+    }
 
-#[inline]
-const fn bool_diff(a: bool, b: bool) -> DiffOp {
-    use DiffOp::*;
+    /// Get the octal digits of the [`Mode`] as a decimal integer (e.g. `755` rather than
+    /// `0o755`), as expected by some APIs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// assert_eq!(755, Mode::from_num("755").unwrap().as_decimal_octal());
+    /// assert_eq!(40, Mode::from_num("040").unwrap().as_decimal_octal());
+    /// assert_eq!(4755, Mode::from_num("4755").unwrap().as_decimal_octal());
+    /// ```
+    #[inline]
+    pub const fn as_decimal_octal(&self) -> u32 {
+        self.special_bits() as u32 * 1000
+            + self.user.bits() as u32 * 100
+            + self.group.bits() as u32 * 10
+            + self.other.bits() as u32
+    }
 
-    if a ^ b {
-        if a {
-            Minus
+    /// Create a [`Mode`] from its octal digits written as a decimal integer (see
+    /// [`Mode::as_decimal_octal`]), returning `None` if the integer is greater than `7777` or any
+    /// of its digits is not octal.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// assert_eq!(Mode::from_num("644").ok(), Mode::from_decimal_octal(644));
+    /// assert_eq!(Mode::from_num("005").ok(), Mode::from_decimal_octal(5));
+    /// assert_eq!(None, Mode::from_decimal_octal(758));
+    /// assert_eq!(Mode::from_num("1777").ok(), Mode::from_decimal_octal(1777));
+    /// assert_eq!(None, Mode::from_decimal_octal(8755));
+    /// ```
+    #[inline]
+    pub const fn from_decimal_octal(n: u32) -> Option<Self> {
+        let (special, user, group, other) = (n / 1000, n / 100 % 10, n / 10 % 10, n % 10);
+        if special > 7 || user > 7 || group > 7 || other > 7 {
+            None
         } else {
-            Plus
+            Some(Self::from_bits(
+                (special << 9 | user << 6 | group << 3 | other) as u16,
+            ))
         }
-    } else {
-        Same
     }
-}
 
-#[cfg(test)]
-mod test {
-    use super::*;
+    /// Create a [`Mode`] from its octal form (see [`Mode::from_num`]) in a `const` context,
+    /// panicking if the input is invalid. An invalid constant fails to compile.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// const M: Mode = Mode::from_num_bytes_const(b"755");
+    /// assert_eq!(Mode::from_num("755").unwrap(), M);
+    ///
+    /// const S: Mode = Mode::from_num_bytes_const(b"1777");
+    /// assert!(S.sticky);
+    /// ```
+    ///
+    /// ```compile_fail
+    /// use cchmod::Mode;
+    ///
+    /// const M: Mode = Mode::from_num_bytes_const(b"758");
+    /// ```
+    pub const fn from_num_bytes_const(num: &[u8]) -> Self {
+        if num.len() != Self::NUM_LEN && num.len() != Self::NUM_SPECIAL_LEN {
+            panic!("a mode must have 3 or 4 octal digits");
+        }
 
-    macro_rules! perm {
-        ($r:expr, $w:expr, $x:expr) => {
-            Perm::new($r, $w, $x)
-        };
-    }
+        let mut bits = 0;
+        let mut i = 0;
+        while i < num.len() {
+            let digit = num[i];
+            if digit < b'0' || digit > b'7' {
+                panic!("a mode must consist of octal digits");
+            }
+            bits = bits << 3 | (digit - b'0') as u16;
+            i += 1;
+        }
 
-    macro_rules! mode {
-        ($u:expr, $g:expr, $o:expr) => {
-            Mode::new($u, $g, $o)
-        };
-        ($ur:expr, $uw:expr, $ux:expr, $gr:expr, $gw:expr, $gx:expr, $or:expr, $ow:expr, $ox: expr) => {
-            Mode::new(
-                perm!($ur, $uw, $ux),
-                perm!($gr, $gw, $gx),
-                perm!($or, $ow, $ox),
-            )
-        };
+        Self::from_bits(bits)
+    }
+
+    /// Get the octal value of each class of the [`Mode`] (user, group, other), a stable layout for
+    /// passing across an FFI boundary. Special bits are not included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// assert_eq!([7, 5, 0], Mode::from_num("750").unwrap().to_u32_array());
+    /// ```
+    #[inline]
+    pub const fn to_u32_array(&self) -> [u32; 3] {
+        [
+            self.user.bits() as u32,
+            self.group.bits() as u32,
+            self.other.bits() as u32,
+        ]
+    }
+
+    /// Create a [`Mode`] from the octal value of each class (see [`Mode::to_u32_array`]),
+    /// returning [`ParseError::OutOfRange`] if any value is greater than 7.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::{Mode, ParseError};
+    ///
+    /// assert_eq!(Mode::from_num("750").unwrap(), Mode::from_u32_array(&[7, 5, 0]).unwrap());
+    /// assert_eq!(
+    ///     ParseError::OutOfRange { index: 1, value: 8 },
+    ///     Mode::from_u32_array(&[7, 8, 0]).unwrap_err()
+    /// );
+    /// ```
+    pub fn from_u32_array(values: &[u32; 3]) -> Result<Self, ParseError> {
+        let mut perms = [perm::EMPTY; 3];
+        for (index, (&value, perm)) in values.iter().zip(perms.iter_mut()).enumerate() {
+            if value > 7 {
+                return Err(ParseError::OutOfRange { index, value });
+            }
+            *perm = Perm::from_bits(value as u8);
+        }
+
+        let [user, group, other] = perms;
+        Ok(Self::new(user, group, other))
+    }
+
+    /// Create a [`Mode`] from its octal form, returning `None` if the input is invalid. This is
+    /// equivalent to [`Mode::from_num`], but avoids constructing a [`ParseError`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// assert_eq!(Mode::from_num("755").ok(), Mode::from_num_opt("755"));
+    /// assert_eq!(None, Mode::from_num_opt("758"));
+    /// assert_eq!(None, Mode::from_num_opt("75"));
+    /// ```
+    #[inline]
+    pub fn from_num_opt(num: &str) -> Option<Self> {
+        let bytes = num.as_bytes();
+        if bytes.len() != Self::NUM_LEN && bytes.len() != Self::NUM_SPECIAL_LEN {
+            return None;
+        }
+
+        let mut bits = 0;
+        for b in bytes {
+            match b {
+                b'0'..=b'7' => bits = bits << 3 | (b - b'0') as u16,
+                _ => return None,
+            }
+        }
+
+        Some(Self::from_bits(bits))
+    }
+
+    /// Create a [`Mode`] from its symbolic form, returning `None` if the input is invalid. This is
+    /// equivalent to [`Mode::from_sym`], but avoids constructing a [`ParseError`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// assert_eq!(Mode::from_sym("rwxr-xr-x").ok(), Mode::from_sym_opt("rwxr-xr-x"));
+    /// assert_eq!(Mode::from_num("5644").ok(), Mode::from_sym_opt("rwSr--r-T"));
+    /// assert_eq!(None, Mode::from_sym_opt("rwxr-xr-w"));
+    /// assert_eq!(None, Mode::from_sym_opt("rwxr-x"));
+    /// ```
+    #[inline]
+    pub fn from_sym_opt(sym: &str) -> Option<Self> {
+        if sym.len() != Self::SYM_LEN {
+            return None;
+        }
+
+        // Any non-ASCII character fails to match, so slicing by bytes is safe.
+        let bytes = sym.as_bytes();
+        let (user, setuid) = Self::class_from_sym_opt(&bytes[0..3], b's')?;
+        let (group, setgid) = Self::class_from_sym_opt(&bytes[3..6], b's')?;
+        let (other, sticky) = Self::class_from_sym_opt(&bytes[6..9], b't')?;
+
+        Some(Self {
+            user,
+            group,
+            other,
+            setuid,
+            setgid,
+            sticky,
+        })
+    }
+
+    /// Get the symbolic representation of the [`Mode`], with `sep` between the classes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// let m = Mode::from_num("755").unwrap();
+    /// assert_eq!("rwx|r-x|r-x", m.as_sym_separated('|'));
+    /// assert_eq!("rwx-r-x-r-x", m.as_sym_separated('-'));
+    /// ```
+    #[inline]
+    pub fn as_sym_separated(&self, sep: char) -> String {
+        let mut sym = String::with_capacity(Self::SYM_LEN + 2 * sep.len_utf8());
+        Self::push_class_sym(&mut sym, &self.user, self.setuid, 's');
+        sym.push(sep);
+        Self::push_class_sym(&mut sym, &self.group, self.setgid, 's');
+        sym.push(sep);
+        Self::push_class_sym(&mut sym, &self.other, self.sticky, 't');
+        sym
+    }
+
+    /// Explain how each octal digit of the [`Mode`] is the sum of the values of the permissions
+    /// it grants (4 for read, 2 for write, and 1 for execute). A digit of 0 is explained as `0`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// assert_eq!(
+    ///     "7 = 4(r)+2(w)+1(x), 5 = 4(r)+1(x), 5 = 4(r)+1(x)",
+    ///     Mode::from_num("755").unwrap().explain_octal()
+    /// );
+    /// assert_eq!(
+    ///     "6 = 4(r)+2(w), 4 = 4(r), 0 = 0",
+    ///     Mode::from_num("640").unwrap().explain_octal()
+    /// );
+    /// ```
+    pub fn explain_octal(&self) -> String {
+        #[inline]
+        fn explain(perm: &Perm) -> String {
+            let terms: Vec<&str> = [
+                (perm.read, "4(r)"),
+                (perm.write, "2(w)"),
+                (perm.execute, "1(x)"),
+            ]
+            .iter()
+            .filter(|(granted, _)| *granted)
+            .map(|(_, term)| *term)
+            .collect();
+
+            if terms.is_empty() {
+                format!("{} = 0", perm.as_num())
+            } else {
+                format!("{} = {}", perm.as_num(), terms.join("+"))
+            }
+        }
+
+        format!(
+            "{}, {}, {}",
+            explain(&self.user),
+            explain(&self.group),
+            explain(&self.other)
+        )
+    }
+
+    /// Get the symbolic representation of each class of the [`Mode`], prefixed by the
+    /// corresponding label in `labels` (user, group, other) and separated by spaces. Special bits
+    /// are shown in the execute slots, as in [`Mode::as_sym`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// let m = Mode::from_num("755").unwrap();
+    /// assert_eq!(
+    ///     "owner=rwx group=r-x other=r-x",
+    ///     m.as_labeled(["owner", "group", "other"])
+    /// );
+    /// assert_eq!(
+    ///     "propriétaire=rwx groupe=r-x autres=r-x",
+    ///     m.as_labeled(["propriétaire", "groupe", "autres"])
+    /// );
+    ///
+    /// let m = Mode::from_num("1777").unwrap();
+    /// assert_eq!("u=rwx g=rwx o=rwt", m.as_labeled(["u", "g", "o"]));
+    /// ```
+    pub fn as_labeled(&self, labels: [&str; 3]) -> String {
+        let mut sym = String::new();
+        for (i, (perm, special, set)) in [
+            (&self.user, self.setuid, 's'),
+            (&self.group, self.setgid, 's'),
+            (&self.other, self.sticky, 't'),
+        ]
+        .iter()
+        .enumerate()
+        {
+            if i > 0 {
+                sym.push(' ');
+            }
+            sym.push_str(labels[i]);
+            sym.push('=');
+            Self::push_class_sym(&mut sym, perm, *special, *set);
+        }
+
+        sym
+    }
+
+    /// Get a table of the permissions of the [`Mode`], with a row for each class and a column for
+    /// each permission. Granted permissions are shown by their symbol, including special bits in
+    /// the execute column (as in [`Mode::as_sym`]), and other cells are left blank.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// assert_eq!(
+    ///     concat!(
+    ///         "      r w x\n",
+    ///         "user  r w s\n",
+    ///         "group r   x\n",
+    ///         "other     x",
+    ///     ),
+    ///     Mode::from_num("4751").unwrap().as_grid()
+    /// );
+    /// ```
+    pub fn as_grid(&self) -> String {
+        let mut grid = String::from("      r w x");
+        for (label, perm, special, set) in [
+            ("user", &self.user, self.setuid, 's'),
+            ("group", &self.group, self.setgid, 's'),
+            ("other", &self.other, self.sticky, 't'),
+        ] {
+            let mut sym = String::new();
+            Self::push_class_sym(&mut sym, perm, special, set);
+
+            grid.push('\n');
+            grid.push_str(&format!("{:<5}", label));
+            for c in sym.chars() {
+                grid.push(' ');
+                grid.push(if c == '-' { ' ' } else { c });
+            }
+        }
+
+        grid
+    }
+
+    /// Create a [`Mode`] from its symbolic form with `sep` between the classes (e.g.
+    /// `rwx|r-x|r-x`), returning [`ParseError`] if the input is invalid. Error positions include
+    /// the separators.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::{Mode, ParseError};
+    ///
+    /// assert_eq!(
+    ///     Mode::from_sym("rwxr-xr-x").unwrap(),
+    ///     Mode::from_sym_separated("rwx|r-x|r-x", '|').unwrap()
+    /// );
+    /// assert_eq!(
+    ///     Mode::from_sym("rwxr-xr-x").unwrap(),
+    ///     Mode::from_sym_separated("rwx-r-x-r-x", '-').unwrap()
+    /// );
+    ///
+    /// assert_eq!(
+    ///     ParseError::UnexpectedChar {
+    ///         pos: 6,
+    ///         c: '|',
+    ///         expected: Some(vec!['x', 's', 'S', '-'])
+    ///     },
+    ///     Mode::from_sym_separated("rwx|r-|r-x", '|').unwrap_err()
+    /// );
+    /// assert_eq!(
+    ///     ParseError::UnexpectedEoi { pos: 7 },
+    ///     Mode::from_sym_separated("rwx|r-x", '|').unwrap_err()
+    /// );
+    /// ```
+    pub fn from_sym_separated(sym: &str, sep: char) -> Result<Self, ParseError> {
+        let mut chars = sym.chars();
+        let mut pos = 0;
+
+        // The classes have a fixed length, so the separator may be any character, even one that
+        // can appear within a class.
+        let separator = |chars: &mut Chars, pos: &mut usize| match chars.next() {
+            None => Err(ParseError::UnexpectedEoi { pos: *pos }),
+            Some(c) if c == sep => {
+                *pos += 1;
+                Ok(())
+            }
+            Some(c) => Err(ParseError::UnexpectedChar {
+                c,
+                pos: *pos,
+                expected: Some(vec![sep]),
+            }),
+        };
+
+        let (user, setuid) = Self::class_from_sym(&mut chars, &mut pos, 's')?;
+        separator(&mut chars, &mut pos)?;
+        let (group, setgid) = Self::class_from_sym(&mut chars, &mut pos, 's')?;
+        separator(&mut chars, &mut pos)?;
+        let (other, sticky) = Self::class_from_sym(&mut chars, &mut pos, 't')?;
+
+        if let Some(c) = chars.next() {
+            Err(ParseError::UnexpectedChar {
+                c,
+                pos,
+                expected: None,
+            })
+        } else {
+            Ok(Self {
+                user,
+                group,
+                other,
+                setuid,
+                setgid,
+                sticky,
+            })
+        }
+    }
+
+    /// Create a [`Mode`] from either its octal or symbolic form, returning [`ParseError`] if the
+    /// input is invalid.
+    ///
+    /// Input beginning with a digit is parsed as octal, and all other input as symbolic. One- and
+    /// two-digit inputs are rejected with [`ParseError::ShortNum`], which also indicates whether
+    /// the input would have been a valid [`Perm`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::{Mode, ParseError};
+    ///
+    /// assert_eq!(Mode::from_num("755").unwrap(), Mode::parse("755").unwrap());
+    /// assert_eq!(Mode::from_num("755").unwrap(), Mode::parse("rwxr-xr-x").unwrap());
+    ///
+    /// assert_eq!(
+    ///     ParseError::ShortNum { len: 1, perm: true },
+    ///     Mode::parse("7").unwrap_err()
+    /// );
+    /// assert_eq!(
+    ///     ParseError::ShortNum { len: 2, perm: false },
+    ///     Mode::parse("75").unwrap_err()
+    /// );
+    /// ```
+    #[inline]
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        if !input.starts_with(|c: char| c.is_ascii_digit()) {
+            return Self::from_sym(input);
+        }
+
+        let len = input.chars().count();
+        if len < 3 && input.chars().all(|c| c.is_ascii_digit()) {
+            Err(ParseError::ShortNum {
+                len,
+                perm: Perm::from_num(input).is_ok(),
+            })
+        } else {
+            Self::from_num(input)
+        }
+    }
+
+    /// Create a [`Mode`] from either its octal or symbolic form in an OS string (see
+    /// [`Mode::parse`]), returning [`ParseError::NonUtf8`] if the input is not valid UTF-8.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::ffi::OsStr;
+    ///
+    /// use cchmod::Mode;
+    ///
+    /// assert_eq!(Mode::parse("755").unwrap(), Mode::from_os_str(OsStr::new("755")).unwrap());
+    /// ```
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn from_os_str(input: &OsStr) -> Result<Self, ParseError> {
+        Self::parse(input.to_str().ok_or(ParseError::NonUtf8)?)
+    }
+
+    /// Create a [`Mode`] from pairs of class names (`user`, `group`, and `other`) and the full
+    /// symbolic forms of their permissions (e.g. `r-x`), returning [`ParseError`] if a key is
+    /// unknown or a value is invalid.
+    ///
+    /// Classes without a pair have no permissions, and later pairs override earlier ones. Error
+    /// positions are relative to the start of the value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::{Mode, ParseError};
+    ///
+    /// let m = Mode::from_kv(&[("user", "rwx"), ("group", "r-x"), ("other", "r--")]).unwrap();
+    /// assert_eq!("754", m.as_num());
+    ///
+    /// let m = Mode::from_kv(&[("user", "rw-")]).unwrap();
+    /// assert_eq!("600", m.as_num());
+    ///
+    /// assert_eq!(
+    ///     ParseError::InvalidKey { key: "owner".to_string() },
+    ///     Mode::from_kv(&[("owner", "rwx")]).unwrap_err()
+    /// );
+    /// ```
+    pub fn from_kv(pairs: &[(&str, &str)]) -> Result<Self, ParseError> {
+        let mut mode = Self::new(perm::EMPTY, perm::EMPTY, perm::EMPTY);
+        for (key, value) in pairs {
+            let class = match *key {
+                "user" => &mut mode.user,
+                "group" => &mut mode.group,
+                "other" => &mut mode.other,
+                key => {
+                    return Err(ParseError::InvalidKey {
+                        key: key.to_string(),
+                    })
+                }
+            };
+            *class = Perm::from_sym_full(value)?;
+        }
+
+        Ok(mode)
+    }
+
+    /// Get the permissions of each class of the [`Mode`] as a map. The setuid, setgid, and sticky
+    /// bits are not included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::{Class, Mode, perm::*};
+    ///
+    /// let map = Mode::from_num("754").unwrap().to_map();
+    /// assert_eq!(3, map.len());
+    /// assert_eq!(Some(&RWX), map.get(&Class::User));
+    /// assert_eq!(Some(&R), map.get(&Class::Other));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn to_map(&self) -> HashMap<Class, Perm> {
+        let mut map = HashMap::with_capacity(3);
+        map.insert(Class::User, self.user);
+        map.insert(Class::Group, self.group);
+        map.insert(Class::Other, self.other);
+        map
+    }
+
+    /// Create a [`Mode`] from a map of the permissions of each class (see [`Mode::to_map`]).
+    /// Classes missing from the map have no permissions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    ///
+    /// use cchmod::{Class, Mode, perm::*};
+    ///
+    /// let mut map = HashMap::new();
+    /// map.insert(Class::User, RW);
+    /// map.insert(Class::Other, R);
+    /// assert_eq!("604", Mode::from_map(&map).as_num());
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn from_map(map: &HashMap<Class, Perm>) -> Self {
+        let class = |class| map.get(&class).copied().unwrap_or(perm::EMPTY);
+        Self::new(class(Class::User), class(Class::Group), class(Class::Other))
+    }
+
+    /// Parse each whitespace-separated token of the input with [`Mode::parse`], returning one
+    /// result per token, in order. Error positions are relative to the start of the token.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::{Mode, ParseError};
+    ///
+    /// let results = Mode::parse_many("755 rw-r--r--\n  75\n");
+    /// assert_eq!(3, results.len());
+    /// assert_eq!(Ok(Mode::from_num("755").unwrap()), results[0]);
+    /// assert_eq!(Ok(Mode::from_num("644").unwrap()), results[1]);
+    /// assert_eq!(Err(ParseError::ShortNum { len: 2, perm: false }), results[2]);
+    /// ```
+    pub fn parse_many(s: &str) -> Vec<Result<Self, ParseError>> {
+        s.split_ascii_whitespace().map(Self::parse).collect()
+    }
+
+    /// Create a [`Mode`] and a [`FileType`] from a raw mode including the file type bits (e.g.
+    /// `st_mode`, as returned by
+    /// [`MetadataExt::mode`](std::os::unix::fs::MetadataExt::mode)). The [`FileType`] is [`None`]
+    /// if the file type bits do not encode a known type (see [`FileType::from_raw`]).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::{FileType, Mode};
+    ///
+    /// assert_eq!(
+    ///     (Mode::from_num("755").unwrap(), Some(FileType::Directory)),
+    ///     Mode::from_raw(0o40755)
+    /// );
+    /// assert_eq!((Mode::from_num("4755").unwrap(), None), Mode::from_raw(0o4755));
+    /// ```
+    #[inline]
+    pub fn from_raw(mode: u32) -> (Self, Option<FileType>) {
+        (Self::from(mode), FileType::from_raw(mode))
+    }
+
+    /// Iterate over every [`Mode`], including those with special bits, in order of their packed
+    /// octal values (see [`Mode::stable_id`]).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// assert_eq!(0o10000, Mode::all().count());
+    /// assert_eq!(Some(Mode::from_num("000").unwrap()), Mode::all().next());
+    /// assert_eq!(Some(Mode::from_num("7777").unwrap()), Mode::all().last());
+    /// ```
+    pub fn all() -> impl Iterator<Item = Self> {
+        (0..0o10000).map(Self::from_bits)
+    }
+
+    /// Collect the distinct modes, sorted by their ordering (see [`Mode`]), so that the special
+    /// bits only break ties between modes with the same permissions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// let modes: Vec<_> = ["755", "644", "755", "4600", "600"]
+    ///     .iter()
+    ///     .map(|num| Mode::from_num(num).unwrap())
+    ///     .collect();
+    /// let sorted: Vec<_> = Mode::sorted_unique(&modes).iter().map(Mode::as_num).collect();
+    /// assert_eq!(vec!["600", "4600", "644", "755"], sorted);
+    /// ```
+    pub fn sorted_unique<'a, I: IntoIterator<Item = &'a Mode>>(modes: I) -> Vec<Mode> {
+        let mut modes: Vec<_> = modes.into_iter().copied().collect();
+        modes.sort();
+        modes.dedup();
+        modes
+    }
+
+    /// Suggest the modes that may have been intended by an input that fails to parse with
+    /// [`Mode::parse`].
+    ///
+    /// If the input is invalid because of a single unexpected character, each character expected
+    /// in its place is tried, and the resulting modes that parse successfully are returned. The
+    /// suggestions therefore differ from each other only in that position. If the input is valid,
+    /// the parsed [`Mode`] is the only suggestion; no suggestions are made for other errors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// let suggestions: Vec<_> = Mode::suggest("rwzr-xr-x").iter().map(|m| m.as_sym()).collect();
+    /// assert_eq!(
+    ///     vec!["rwxr-xr-x", "rwsr-xr-x", "rwSr-xr-x", "rw-r-xr-x"],
+    ///     suggestions
+    /// );
+    ///
+    /// assert_eq!(8, Mode::suggest("7a5").len());
+    /// assert_eq!(vec![Mode::from_num("755").unwrap()], Mode::suggest("755"));
+    /// assert!(Mode::suggest("rwzr-xr-xz").is_empty());
+    /// ```
+    pub fn suggest(input: &str) -> Vec<Self> {
+        let (pos, expected) = match Self::parse(input) {
+            Ok(mode) => return vec![mode],
+            Err(ParseError::UnexpectedChar {
+                pos,
+                expected: Some(expected),
+                ..
+            }) => (pos, expected),
+            Err(_) => return Vec::new(),
+        };
+
+        expected
+            .into_iter()
+            .filter_map(|e| {
+                let fixed: String = input
+                    .chars()
+                    .enumerate()
+                    .map(|(i, c)| if i == pos { e } else { c })
+                    .collect();
+                Self::parse(&fixed).ok()
+            })
+            .collect()
+    }
+
+    /// Create a [`Mode`] from the start of a string, in either octal or symbolic form, returning
+    /// the [`Mode`] and the number of characters consumed. Input beginning with a digit is parsed
+    /// as octal, and all other input as symbolic.
+    ///
+    /// Unlike [`Mode::parse`], trailing input is permitted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::{Mode, ParseError};
+    ///
+    /// let (m, len) = Mode::parse_prefix("755 rest").unwrap();
+    /// assert_eq!("755", m.as_num());
+    /// assert_eq!(3, len);
+    ///
+    /// let (m, len) = Mode::parse_prefix("4755 rest").unwrap();
+    /// assert_eq!("4755", m.as_num());
+    /// assert_eq!(4, len);
+    ///
+    /// let (m, len) = Mode::parse_prefix("rw-r--r--, rest").unwrap();
+    /// assert_eq!("644", m.as_num());
+    /// assert_eq!(9, len);
+    ///
+    /// assert_eq!(
+    ///     ParseError::UnexpectedEoi { pos: 2 },
+    ///     Mode::parse_prefix("75").unwrap_err()
+    /// );
+    /// ```
+    #[inline]
+    pub fn parse_prefix(s: &str) -> Result<(Self, usize), ParseError> {
+        if s.starts_with(|c: char| c.is_ascii_digit()) {
+            let len = if matches!(s.chars().nth(Self::NUM_LEN), Some('0'..='7')) {
+                Self::NUM_SPECIAL_LEN
+            } else {
+                Self::NUM_LEN
+            };
+            Ok((Self::from_num(prefix(s, len))?, len))
+        } else {
+            Ok((Self::from_sym(prefix(s, Self::SYM_LEN))?, Self::SYM_LEN))
+        }
+    }
+
+    /// Find the first [`Mode`], in either octal or symbolic form (see [`Mode::parse_prefix`]), in
+    /// a string, returning it and the byte offset at which it begins.
+    ///
+    /// Octal modes must not be part of a longer run of digits, so that `12345` does not contain a
+    /// mode. Symbolic modes may be adjacent to other text, as in the output of `ls -l` (e.g.
+    /// `drwxr-xr-x`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// assert_eq!(
+    ///     Some((Mode::from_num("755").unwrap(), 8)),
+    ///     Mode::find_first("perm is 755 now")
+    /// );
+    /// assert_eq!(
+    ///     Some((Mode::from_num("755").unwrap(), 1)),
+    ///     Mode::find_first("drwxr-xr-x 2 root root 4096 .")
+    /// );
+    /// assert_eq!(None, Mode::find_first("build 12345 passed"));
+    /// ```
+    pub fn find_first(text: &str) -> Option<(Self, usize)> {
+        let is_digit = |c: Option<char>| matches!(c, Some('0'..='9'));
+
+        let mut prev = None;
+        for (i, c) in text.char_indices() {
+            if let Ok((mode, len)) = Self::parse_prefix(&text[i..]) {
+                let numeric = c.is_ascii_digit();
+                if !numeric || !(is_digit(prev) || is_digit(text[i..].chars().nth(len))) {
+                    return Some((mode, i));
+                }
+            }
+            prev = Some(c);
+        }
+
+        None
+    }
+
+    /// Compute the diff ([`ModeDiff`]) between two modes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::{Mode, ModeDiff, PermDiff, DiffOp::*};
+    ///
+    /// let a = Mode::from_num("777").unwrap();
+    /// let b = Mode::from_num("644").unwrap();
+    ///
+    /// assert_eq!(
+    ///     ModeDiff {
+    ///         user: PermDiff { read: Same, write: Same, execute: Minus },
+    ///         group: PermDiff { read: Same, write: Minus, execute: Minus },
+    ///         other: PermDiff { read: Same, write: Minus, execute: Minus },
+    ///         setuid: Same,
+    ///         setgid: Same,
+    ///         sticky: Same,
+    ///     },
+    ///     a.diff(&b)
+    /// );
+    /// ```
+    #[inline]
+    pub const fn diff(&self, other: &Self) -> ModeDiff {
+        ModeDiff {
+            user: self.user.diff(&other.user),
+            group: self.group.diff(&other.group),
+            other: self.other.diff(&other.other),
+            setuid: bool_diff(self.setuid, other.setuid),
+            setgid: bool_diff(self.setgid, other.setgid),
+            sticky: bool_diff(self.sticky, other.sticky),
+        }
+    }
+
+    /// Apply a diff ([`ModeDiff`]) to the [`Mode`] (see [`Perm::apply`]), so that applying the
+    /// diff from `a` to `b` to `a` produces `b`. Special bits left unchanged by the diff are kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// let a = Mode::from_num("777").unwrap();
+    /// let b = Mode::from_num("644").unwrap();
+    /// let diff = a.diff(&b);
+    ///
+    /// assert_eq!(b, a.apply(&diff));
+    /// assert_eq!("644", Mode::from_num("755").unwrap().apply(&diff).as_num());
+    /// assert_eq!("4644", Mode::from_num("4755").unwrap().apply(&diff).as_num());
+    ///
+    /// let setuid = Mode::from_num("755").unwrap().diff(&Mode::from_num("4755").unwrap());
+    /// assert_eq!("4644", Mode::from_num("644").unwrap().apply(&setuid).as_num());
+    /// ```
+    #[inline]
+    pub const fn apply(&self, diff: &ModeDiff) -> Self {
+        Self {
+            user: self.user.apply(&diff.user),
+            group: self.group.apply(&diff.group),
+            other: self.other.apply(&diff.other),
+            setuid: bool_apply(self.setuid, &diff.setuid),
+            setgid: bool_apply(self.setgid, &diff.setgid),
+            sticky: bool_apply(self.sticky, &diff.sticky),
+        }
+    }
+
+    /// Apply a umask to the [`Mode`], revoking every permission granted by `umask`. The special
+    /// bits of the [`Mode`] are kept, and those of `umask` are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// let umask = Mode::from_num("022").unwrap();
+    /// assert_eq!("755", Mode::from_num("777").unwrap().apply_umask(&umask).as_num());
+    /// assert_eq!("644", Mode::from_num("666").unwrap().apply_umask(&umask).as_num());
+    /// ```
+    #[inline]
+    pub fn apply_umask(&self, umask: &Self) -> Self {
+        self.with_perms(
+            self.user & !umask.user,
+            self.group & !umask.group,
+            self.other & !umask.other,
+        )
+    }
+
+    /// Compute the umask that produces `target` when applied to `base` (see
+    /// [`Mode::apply_umask`]), i.e. the permissions granted by `base` but not by `target`.
+    /// Permissions granted by `target` but not by `base` cannot be produced by any umask, and are
+    /// ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// let base = Mode::from_num("777").unwrap();
+    /// let target = Mode::from_num("755").unwrap();
+    /// let umask = Mode::implied_umask(&base, &target);
+    ///
+    /// assert_eq!("022", umask.as_num());
+    /// assert_eq!(target, base.apply_umask(&umask));
+    /// ```
+    #[inline]
+    pub fn implied_umask(base: &Self, target: &Self) -> Self {
+        Self::new(
+            base.user & !target.user,
+            base.group & !target.group,
+            base.other & !target.other,
+        )
+    }
+
+    /// Create a [`Mode`] by applying `umask` to `base` (see [`Mode::apply_umask`]).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// let base = Mode::from_num("666").unwrap();
+    /// let umask = Mode::from_num("022").unwrap();
+    /// assert_eq!("644", Mode::with_umask(base, umask).as_num());
+    /// ```
+    #[inline]
+    pub fn with_umask(base: Self, umask: Self) -> Self {
+        base.apply_umask(&umask)
+    }
+
+    /// Create the [`Mode`] of a newly created file or directory given the octal form of a process
+    /// umask (e.g. `022`), returning [`ParseError`] if the umask is invalid. The umask is applied
+    /// to `777` for directories and to `666` for files.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// assert_eq!("755", Mode::from_umask_num("022", true).unwrap().as_num());
+    /// assert_eq!("644", Mode::from_umask_num("022", false).unwrap().as_num());
+    /// assert_eq!("640", Mode::from_umask_num("0027", false).unwrap().as_num());
+    /// assert!(Mode::from_umask_num("088", false).is_err());
+    /// ```
+    #[inline]
+    pub fn from_umask_num(umask: &str, is_dir: bool) -> Result<Self, ParseError> {
+        let base = if is_dir { 0o777 } else { 0o666 };
+        Ok(Self::with_umask(
+            Self::from_bits(base),
+            Self::from_num(umask)?,
+        ))
+    }
+
+    /// Describe the change of the mode of `path` from this [`Mode`] to `target`, in the format of
+    /// GNU chmod's `--changes` output. If the modes are equal, the format of its `--verbose`
+    /// output for an unchanged file is used instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// let a = Mode::from_num("755").unwrap();
+    /// let b = Mode::from_num("644").unwrap();
+    ///
+    /// assert_eq!(
+    ///     "mode of 'file' changed from 0755 (rwxr-xr-x) to 0644 (rw-r--r--)",
+    ///     a.as_chmod_changes(&b, "file")
+    /// );
+    /// assert_eq!(
+    ///     "mode of 'file' retained as 0755 (rwxr-xr-x)",
+    ///     a.as_chmod_changes(&a, "file")
+    /// );
+    /// ```
+    pub fn as_chmod_changes(&self, target: &Self, path: &str) -> String {
+        if self == target {
+            format!(
+                "mode of '{}' retained as {:04o} ({})",
+                path,
+                self.bits(),
+                self.as_sym_static()
+            )
+        } else {
+            format!(
+                "mode of '{}' changed from {:04o} ({}) to {:04o} ({})",
+                path,
+                self.bits(),
+                self.as_sym_static(),
+                target.bits(),
+                target.as_sym_static()
+            )
+        }
+    }
+
+    /// Count the number of permissions granted across all classes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// assert_eq!(9, Mode::from_num("777").unwrap().total_bits());
+    /// assert_eq!(6, Mode::from_num("751").unwrap().total_bits());
+    /// ```
+    #[inline]
+    pub const fn total_bits(&self) -> u32 {
+        self.user.count() + self.group.count() + self.other.count()
+    }
+
+    /// Get a permissiveness score from 0 to 9, the number of permissions granted across all
+    /// classes (see [`Mode::total_bits`]).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// assert_eq!(0, Mode::from_num("000").unwrap().permissiveness_score());
+    /// assert_eq!(6, Mode::from_num("751").unwrap().permissiveness_score());
+    /// ```
+    #[inline]
+    pub const fn permissiveness_score(&self) -> u8 {
+        self.total_bits() as u8
+    }
+
+    /// Get the fraction of all permissions that are granted, from 0.0 to 1.0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// assert_eq!(1.0, Mode::from_num("777").unwrap().permissiveness_ratio());
+    /// assert_eq!(6.0 / 9.0, Mode::from_num("751").unwrap().permissiveness_ratio());
+    /// ```
+    #[inline]
+    pub fn permissiveness_ratio(&self) -> f32 {
+        f32::from(self.permissiveness_score()) / 9.0
+    }
+
+    /// Check whether a user with the given relation to the object is granted all of the
+    /// permissions in `want`. Only the class selected by `relation` is considered; superuser
+    /// privileges are not.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::{Mode, OwnershipRelation, perm::*};
+    ///
+    /// let m = Mode::from_num("640").unwrap();
+    ///
+    /// assert!(m.can_access(OwnershipRelation::Owner, RW));
+    /// assert!(m.can_access(OwnershipRelation::Group, R));
+    /// assert!(!m.can_access(OwnershipRelation::Group, RW));
+    /// assert!(!m.can_access(OwnershipRelation::Other, R));
+    /// assert!(m.can_access(OwnershipRelation::Other, EMPTY));
+    /// ```
+    #[inline]
+    pub const fn can_access(&self, relation: OwnershipRelation, want: Perm) -> bool {
+        self.class(relation).contains(&want)
+    }
+
+    /// Get the permissions effectively granted to a user with the given relation to the object.
+    ///
+    /// If `is_root` is set, the user is treated as the superuser, who is granted read and write
+    /// permission regardless of the mode, and execute permission if any class has execute
+    /// permission. Otherwise, the class selected by `relation` applies.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::{Mode, OwnershipRelation, perm::*};
+    ///
+    /// let m = Mode::from_num("600").unwrap();
+    /// assert_eq!(RW, m.effective_perm(OwnershipRelation::Owner, false));
+    /// assert_eq!(EMPTY, m.effective_perm(OwnershipRelation::Other, false));
+    /// assert_eq!(RW, m.effective_perm(OwnershipRelation::Other, true));
+    ///
+    /// let m = Mode::from_num("601").unwrap();
+    /// assert_eq!(RWX, m.effective_perm(OwnershipRelation::Group, true));
+    /// ```
+    #[inline]
+    pub const fn effective_perm(&self, relation: OwnershipRelation, is_root: bool) -> Perm {
+        if is_root {
+            Perm::new(
+                true,
+                true,
+                self.user.execute || self.group.execute || self.other.execute,
+            )
+        } else {
+            *self.class(relation)
+        }
+    }
+
+    /// Get the permissions granted to a process by the class that applies to it: the user class
+    /// if `is_owner` is set, else the group class if `in_group` is set, else the other class.
+    ///
+    /// As in POSIX, only the first matching class applies, so an owner is granted only the
+    /// permissions of the user class even if the group class is more permissive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::{Mode, perm::*};
+    ///
+    /// let m = Mode::from_num("460").unwrap();
+    /// assert_eq!(R, m.access_for(true, true));
+    /// assert_eq!(RW, m.access_for(false, true));
+    /// assert_eq!(EMPTY, m.access_for(false, false));
+    /// ```
+    #[inline]
+    pub const fn access_for(&self, is_owner: bool, in_group: bool) -> Perm {
+        let relation = if is_owner {
+            OwnershipRelation::Owner
+        } else if in_group {
+            OwnershipRelation::Group
+        } else {
+            OwnershipRelation::Other
+        };
+        *self.class(relation)
+    }
+
+    /// Check whether two modes grant the same permissions to the user class, regardless of the
+    /// other classes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// let a = Mode::from_num("755").unwrap();
+    /// let b = Mode::from_num("744").unwrap();
+    /// assert!(a.same_owner_perms(&b));
+    /// assert!(a.same_owner_perms(&Mode::from_num("700").unwrap()));
+    /// assert!(!a.same_owner_perms(&Mode::from_num("655").unwrap()));
+    /// ```
+    #[inline]
+    pub const fn same_owner_perms(&self, other: &Self) -> bool {
+        self.user.bits() == other.user.bits()
+    }
+
+    /// Check whether two modes grant the same permissions to the group class, regardless of the
+    /// other classes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// let a = Mode::from_num("755").unwrap();
+    /// let b = Mode::from_num("744").unwrap();
+    /// assert!(!a.same_group_perms(&b));
+    /// assert!(a.same_group_perms(&Mode::from_num("050").unwrap()));
+    /// assert!(!a.same_group_perms(&Mode::from_num("765").unwrap()));
+    /// ```
+    #[inline]
+    pub const fn same_group_perms(&self, other: &Self) -> bool {
+        self.group.bits() == other.group.bits()
+    }
+
+    /// Check whether two modes grant the same permissions to the other class, regardless of the
+    /// other classes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// let a = Mode::from_num("755").unwrap();
+    /// let b = Mode::from_num("744").unwrap();
+    /// assert!(!a.same_other_perms(&b));
+    /// assert!(a.same_other_perms(&Mode::from_num("005").unwrap()));
+    /// assert!(!a.same_other_perms(&Mode::from_num("754").unwrap()));
+    /// ```
+    #[inline]
+    pub const fn same_other_perms(&self, other: &Self) -> bool {
+        self.other.bits() == other.other.bits()
+    }
+
+    /// Check whether every class of the [`Mode`] is granted the same permissions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// assert!(Mode::from_num("777").unwrap().is_symmetric());
+    /// assert!(Mode::from_num("000").unwrap().is_symmetric());
+    /// assert!(!Mode::from_num("755").unwrap().is_symmetric());
+    /// ```
+    #[inline]
+    pub const fn is_symmetric(&self) -> bool {
+        let user = self.user.bits();
+        user == self.group.bits() && user == self.other.bits()
+    }
+
+    /// Get a small identifier for the [`Mode`] that is stable across runs, platforms, and
+    /// versions of this crate, unlike the output of a [`Hasher`](std::hash::Hasher). This is the
+    /// canonical key for caches of modes.
+    ///
+    /// The identifier is the packed octal value of the [`Mode`], including the special bits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// assert_eq!(0o755, Mode::from_num("755").unwrap().stable_id());
+    /// assert_eq!(0o4755, Mode::from_num("4755").unwrap().stable_id());
+    /// ```
+    #[inline]
+    pub const fn stable_id(&self) -> u16 {
+        self.bits()
+    }
+
+    /// Get the [`Mode`] with the setuid, setgid, and sticky bits cleared.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// assert_eq!("755", Mode::from_num("4755").unwrap().without_special().as_num());
+    /// assert_eq!("rwxrwx---", Mode::from_num("3770").unwrap().without_special().as_sym());
+    /// ```
+    #[inline]
+    pub const fn without_special(&self) -> Self {
+        Self::from_bits(self.bits() & 0o777)
+    }
+
+    /// Check whether two modes grant the same read, write, and execute permissions to every
+    /// class, ignoring the setuid, setgid, and sticky bits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// let a = Mode::from_num("755").unwrap();
+    /// assert!(a.eq_ignoring_special(&Mode::from_num("755").unwrap()));
+    /// assert!(a.eq_ignoring_special(&Mode::from_num("4755").unwrap()));
+    /// assert!(!a.eq_ignoring_special(&Mode::from_num("754").unwrap()));
+    /// assert_ne!(a, Mode::from_num("4755").unwrap());
+    /// ```
+    #[inline]
+    pub const fn eq_ignoring_special(&self, other: &Self) -> bool {
+        self.bits() & 0o777 == other.bits() & 0o777
+    }
+
+    /// Check whether every permission and special bit set in `other` is also set in this
+    /// [`Mode`], class by class (see [`Perm::contains`]). This is a subset relation, unlike the
+    /// ordering of modes; it can be used to check that a mode is no more permissive than a policy.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// let policy = Mode::from_num("755").unwrap();
+    /// assert!(policy.contains(&Mode::from_num("644").unwrap()));
+    /// assert!(policy.contains(&policy));
+    /// assert!(!policy.contains(&Mode::from_num("664").unwrap()));
+    /// assert!(!policy.contains(&Mode::from_num("4755").unwrap()));
+    ///
+    /// // "640" > "606", but neither contains the other.
+    /// assert!(!Mode::from_num("640").unwrap().contains(&Mode::from_num("606").unwrap()));
+    /// assert!(!Mode::from_num("606").unwrap().contains(&Mode::from_num("640").unwrap()));
+    /// ```
+    #[inline]
+    pub const fn contains(&self, other: &Self) -> bool {
+        other.bits() & !self.bits() == 0
+    }
+
+    /// Check whether the [`Mode`] matches a symbolic pattern (see [`Mode::as_sym`]) in which `?`
+    /// matches any value of a slot, returning [`ParseError`] if the pattern is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::{Mode, ParseError};
+    ///
+    /// let mode = |num| Mode::from_num(num).unwrap();
+    /// assert!(mode("744").matches_pattern("rw?r--r--").unwrap());
+    /// assert!(mode("644").matches_pattern("rw?r--r--").unwrap());
+    /// assert!(!mode("664").matches_pattern("rw?r--r--").unwrap());
+    /// assert!(mode("4755").matches_pattern("??s??????").unwrap());
+    ///
+    /// assert_eq!(
+    ///     ParseError::UnexpectedChar { pos: 1, c: '*', expected: Some(vec!['w', '-', '?']) },
+    ///     mode("644").matches_pattern("r*?r--r--").unwrap_err()
+    /// );
+    /// ```
+    pub fn matches_pattern(&self, pattern: &str) -> Result<bool, ParseError> {
+        let sym: Vec<char> = self.as_sym_static().chars().collect();
+        let mut chars = pattern.chars();
+        let mut matches = true;
+
+        for (i, set) in ['s', 's', 't'].iter().enumerate() {
+            let options = [
+                vec!['r', '-', '?'],
+                vec!['w', '-', '?'],
+                vec!['x', *set, set.to_ascii_uppercase(), '-', '?'],
+            ];
+
+            for (j, options) in options.iter().enumerate() {
+                let pos = i * Perm::SYM_FULL_LEN + j;
+                match chars.next() {
+                    Some(c) if options.contains(&c) => matches &= c == '?' || c == sym[pos],
+                    Some(c) => {
+                        return Err(ParseError::UnexpectedChar {
+                            pos,
+                            c,
+                            expected: Some(options.clone()),
+                        })
+                    }
+                    None => return Err(ParseError::UnexpectedEoi { pos }),
+                }
+            }
+        }
+
+        match chars.next() {
+            Some(c) => Err(ParseError::UnexpectedChar {
+                pos: Self::SYM_LEN,
+                c,
+                expected: None,
+            }),
+            None => Ok(matches),
+        }
+    }
+
+    /// Set the permissions of the user class.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::{perm::*, Mode};
+    ///
+    /// let mut m = Mode::default();
+    /// m.set_user(RWX);
+    /// m.set_group(RX);
+    /// m.set_other(R);
+    /// assert_eq!("754", m.as_num());
+    /// ```
+    #[inline]
+    pub fn set_user(&mut self, perm: Perm) {
+        self.user = perm;
+    }
+
+    /// Set the permissions of the group class.
+    #[inline]
+    pub fn set_group(&mut self, perm: Perm) {
+        self.group = perm;
+    }
+
+    /// Set the permissions of the other class.
+    #[inline]
+    pub fn set_other(&mut self, perm: Perm) {
+        self.other = perm;
+    }
+
+    /// Count the number of permissions that differ between two modes. The special bits are not
+    /// permissions and are not counted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// let a = Mode::from_num("644").unwrap();
+    /// assert_eq!(0, a.distance(&a));
+    /// assert_eq!(3, a.distance(&Mode::from_num("755").unwrap()));
+    /// assert_eq!(2, a.distance(&Mode::from_num("600").unwrap()));
+    /// assert_eq!(0, a.distance(&Mode::from_num("4644").unwrap()));
+    /// ```
+    #[inline]
+    pub const fn distance(&self, other: &Self) -> u32 {
+        ((self.bits() ^ other.bits()) & 0o777).count_ones()
+    }
+
+    /// Check whether at most `n` permissions differ between two modes (see [`Mode::distance`]).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// let a = Mode::from_num("644").unwrap();
+    /// let b = Mode::from_num("755").unwrap();
+    ///
+    /// assert!(a.differs_by_at_most(&a, 0));
+    /// assert!(!a.differs_by_at_most(&b, 0));
+    /// assert!(!a.differs_by_at_most(&b, 1));
+    /// assert!(a.differs_by_at_most(&Mode::from_num("645").unwrap(), 1));
+    /// assert!(a.differs_by_at_most(&b, 3));
+    /// ```
+    #[inline]
+    pub const fn differs_by_at_most(&self, other: &Self, n: usize) -> bool {
+        self.distance(other) as usize <= n
+    }
+
+    /// Get the nine modes that differ from this one by exactly one permission, in order from the
+    /// user's read permission to the other class's execute permission.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// let m = Mode::from_num("644").unwrap();
+    /// let neighbors: Vec<_> = m.neighbors().map(|n| n.as_num()).collect();
+    ///
+    /// assert_eq!(
+    ///     vec!["244", "444", "744", "604", "664", "654", "640", "646", "645"],
+    ///     neighbors
+    /// );
+    /// ```
+    #[inline]
+    pub fn neighbors(&self) -> impl Iterator<Item = Mode> {
+        let bits = self.bits();
+        (0..9).rev().map(move |i| Self::from_bits(bits ^ 1 << i))
+    }
+
+    /// Compute the diff ([`ModeDiff`]) from a baseline to each of several modes, in order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// let baseline = Mode::from_num("644").unwrap();
+    /// let modes = vec![
+    ///     Mode::from_num("644").unwrap(),
+    ///     Mode::from_num("755").unwrap(),
+    ///     Mode::from_num("600").unwrap(),
+    /// ];
+    ///
+    /// let diffs = Mode::diff_against(&baseline, &modes);
+    /// assert_eq!(
+    ///     vec!["", "u+x,g+x,o+x", "g-r,o-r"],
+    ///     diffs.iter().map(|d| d.to_string()).collect::<Vec<_>>()
+    /// );
+    /// assert_eq!(baseline.diff(&modes[1]), diffs[1]);
+    /// ```
+    pub fn diff_against<'a, I: IntoIterator<Item = &'a Mode>>(
+        baseline: &Mode,
+        modes: I,
+    ) -> Vec<ModeDiff> {
+        modes.into_iter().map(|mode| baseline.diff(mode)).collect()
+    }
+
+    /// Conservatively tighten the [`Mode`] by revoking write permission from the group and other
+    /// classes, and execute permission from the other class. User permissions and all read
+    /// permissions are left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// assert_eq!("754", Mode::from_num("777").unwrap().downgrade().as_num());
+    /// assert_eq!("644", Mode::from_num("666").unwrap().downgrade().as_num());
+    /// assert_eq!("644", Mode::from_num("644").unwrap().downgrade().as_num());
+    /// ```
+    #[inline]
+    pub const fn downgrade(&self) -> Self {
+        self.with_perms(
+            self.user,
+            Perm::new(self.group.read, false, self.group.execute),
+            Perm::new(self.other.read, false, false),
+        )
+    }
+
+    /// Grant execute permission to every class that has read permission, similar to how chmod's
+    /// `+X` is commonly used to make a file runnable by those who can read it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// assert_eq!("755", Mode::from_num("644").unwrap().make_executable().as_num());
+    /// assert_eq!("750", Mode::from_num("640").unwrap().make_executable().as_num());
+    /// assert_eq!("720", Mode::from_num("620").unwrap().make_executable().as_num());
+    /// ```
+    #[inline]
+    pub const fn make_executable(&self) -> Self {
+        self.with_perms(
+            Perm::new(
+                self.user.read,
+                self.user.write,
+                self.user.execute || self.user.read,
+            ),
+            Perm::new(
+                self.group.read,
+                self.group.write,
+                self.group.execute || self.group.read,
+            ),
+            Perm::new(
+                self.other.read,
+                self.other.write,
+                self.other.execute || self.other.read,
+            ),
+        )
+    }
+
+    /// Swap the read and write permissions within each class (see [`Perm::swap_read_write`]).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// let m = Mode::from_num("741").unwrap();
+    /// assert_eq!("721", m.swap_read_write().as_num());
+    /// ```
+    #[inline]
+    pub const fn swap_read_write(&self) -> Self {
+        self.with_perms(
+            self.user.swap_read_write(),
+            self.group.swap_read_write(),
+            self.other.swap_read_write(),
+        )
+    }
+
+    /// Swap the read and execute permissions within each class (see
+    /// [`Perm::swap_read_execute`]).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// let m = Mode::from_num("741").unwrap();
+    /// assert_eq!("714", m.swap_read_execute().as_num());
+    /// ```
+    #[inline]
+    pub const fn swap_read_execute(&self) -> Self {
+        self.with_perms(
+            self.user.swap_read_execute(),
+            self.group.swap_read_execute(),
+            self.other.swap_read_execute(),
+        )
+    }
+
+    /// Describe the changes from this [`Mode`] to another as phrases such as `"group gained
+    /// execute"` or `"other lost write"`. Phrases are ordered by class (user, group, other), then
+    /// by permission (read, write, execute).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// let a = Mode::from_num("644").unwrap();
+    /// let b = Mode::from_num("755").unwrap();
+    ///
+    /// assert_eq!(
+    ///     vec!["user gained execute", "group gained execute", "other gained execute"],
+    ///     a.changes_between(&b)
+    /// );
+    ///
+    /// let c = Mode::from_num("620").unwrap();
+    /// assert_eq!(
+    ///     vec!["group lost read", "group gained write", "other lost read"],
+    ///     a.changes_between(&c)
+    /// );
+    /// ```
+    pub fn changes_between(&self, other: &Self) -> Vec<String> {
+        let diff = self.diff(other);
+
+        let mut changes = Vec::new();
+        for (class, diff) in [
+            ("user", &diff.user),
+            ("group", &diff.group),
+            ("other", &diff.other),
+        ] {
+            for (perm, op) in [
+                ("read", &diff.read),
+                ("write", &diff.write),
+                ("execute", &diff.execute),
+            ] {
+                let action = match op {
+                    DiffOp::Plus => "gained",
+                    DiffOp::Minus => "lost",
+                    DiffOp::Same => continue,
+                };
+                changes.push(format!("{} {} {}", class, action, perm));
+            }
+        }
+
+        changes
+    }
+
+    /// Clamp the [`Mode`] so that each class grants at least the permissions in `min` and at most
+    /// the permissions in `max` (see [`Perm::clamp`]). Unlike [`Ord::clamp`], this is not based
+    /// on the ordering of modes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// let m = Mode::from_num("640").unwrap();
+    /// let min = Mode::from_num("600").unwrap();
+    /// let max = Mode::from_num("644").unwrap();
+    /// assert_eq!("640", m.clamp(&min, &max).as_num());
+    ///
+    /// let m = Mode::from_num("777").unwrap();
+    /// let min = Mode::from_num("000").unwrap();
+    /// let max = Mode::from_num("755").unwrap();
+    /// assert_eq!("755", m.clamp(&min, &max).as_num());
+    /// ```
+    #[inline]
+    pub const fn clamp(self, min: &Self, max: &Self) -> Self {
+        self.with_perms(
+            self.user.clamp(&min.user, &max.user),
+            self.group.clamp(&min.group, &max.group),
+            self.other.clamp(&min.other, &max.other),
+        )
+    }
+
+    /// Call [`ModeVisitor::visit_class`] with the permissions of each class, in the order user,
+    /// group, other.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::{traits::ModeVisitor, Class, Mode, Perm};
+    ///
+    /// struct Concat(String);
+    ///
+    /// impl ModeVisitor for Concat {
+    ///     fn visit_class(&mut self, _class: Class, perm: Perm) {
+    ///         self.0.push_str(&perm.as_sym_full());
+    ///     }
+    /// }
+    ///
+    /// let mut v = Concat(String::new());
+    /// Mode::from_num("754").unwrap().accept(&mut v);
+    /// assert_eq!("rwxr-xr--", v.0);
+    /// ```
+    #[inline]
+    pub fn accept<V: ModeVisitor>(&self, v: &mut V) {
+        for (class, perm) in [
+            (Class::User, &self.user),
+            (Class::Group, &self.group),
+            (Class::Other, &self.other),
+        ] {
+            v.visit_class(class, *perm);
+        }
+    }
+
+    /// Check the [`Mode`] for common misconfigurations, returning a [`ModeWarning`] for each
+    /// one found, in the order the variants are declared.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::{Mode, ModeWarning};
+    ///
+    /// assert_eq!(
+    ///     vec![ModeWarning::WorldWritable, ModeWarning::GroupWritableNoSticky],
+    ///     Mode::from_num("666").unwrap().lint()
+    /// );
+    /// assert_eq!(
+    ///     vec![ModeWarning::ExecutableButNotReadable],
+    ///     Mode::from_num("711").unwrap().lint()
+    /// );
+    /// assert_eq!(
+    ///     vec![ModeWarning::WorldWritable, ModeWarning::SetuidWithWrite],
+    ///     Mode::from_num("4757").unwrap().lint()
+    /// );
+    /// assert!(Mode::from_num("755").unwrap().lint().is_empty());
+    /// assert!(Mode::from_num("1775").unwrap().lint().is_empty());
+    /// ```
+    pub fn lint(&self) -> Vec<ModeWarning> {
+        let mut warnings = Vec::new();
+        if self.other.write {
+            warnings.push(ModeWarning::WorldWritable);
+        }
+        if self.group.write && !self.sticky {
+            warnings.push(ModeWarning::GroupWritableNoSticky);
+        }
+        if [&self.user, &self.group, &self.other]
+            .iter()
+            .any(|perm| perm.execute && !perm.read)
+        {
+            warnings.push(ModeWarning::ExecutableButNotReadable);
+        }
+        if self.setuid && (self.group.write || self.other.write) {
+            warnings.push(ModeWarning::SetuidWithWrite);
+        }
+
+        warnings
+    }
+}
+
+impl Mode {
+    /// Get the permissions of the class that applies to a user with the given relation.
+    #[inline]
+    const fn class(&self, relation: OwnershipRelation) -> &Perm {
+        match relation {
+            OwnershipRelation::Owner => &self.user,
+            OwnershipRelation::Group => &self.group,
+            OwnershipRelation::Other => &self.other,
+        }
+    }
+
+    /// Create a [`Mode`] with the given permissions and the special bits of this one.
+    #[inline]
+    const fn with_perms(&self, user: Perm, group: Perm, other: Perm) -> Self {
+        Self {
+            user,
+            group,
+            other,
+            setuid: self.setuid,
+            setgid: self.setgid,
+            sticky: self.sticky,
+        }
+    }
+
+    /// Get the value of the special bits digit of the [`Mode`] (setuid 4, setgid 2, sticky 1).
+    #[inline]
+    const fn special_bits(&self) -> u8 {
+        (self.setuid as u8) << 2 | (self.setgid as u8) << 1 | self.sticky as u8
+    }
+
+    /// Get the packed octal value of the [`Mode`], including the special bits (e.g. `0o4755`).
+    #[inline]
+    const fn bits(&self) -> u16 {
+        (self.special_bits() as u16) << 9
+            | (self.user.bits() as u16) << 6
+            | (self.group.bits() as u16) << 3
+            | self.other.bits() as u16
+    }
+
+    /// Create a [`Mode`] from the low twelve bits of a packed octal value.
+    #[inline]
+    const fn from_bits(bits: u16) -> Self {
+        Self {
+            user: Perm::from_bits((bits >> 6) as u8),
+            group: Perm::from_bits((bits >> 3) as u8),
+            other: Perm::from_bits(bits as u8),
+            setuid: bits & 0o4000 != 0,
+            setgid: bits & 0o2000 != 0,
+            sticky: bits & 0o1000 != 0,
+        }
+    }
+
+    /// Append the symbolic representation of a class to a string, with `set` (or its uppercase
+    /// form, if the class lacks execute permission) in the execute position if `special` is set.
+    #[inline]
+    fn push_class_sym(sym: &mut String, perm: &Perm, special: bool, set: char) {
+        perm.push_sym_full(sym);
+        if special {
+            sym.pop();
+            sym.push(if perm.execute {
+                set
+            } else {
+                set.to_ascii_uppercase()
+            });
+        }
+    }
+
+    /// Parse a symbolic [`Mode`] from `chars`, starting at `pos`, followed by an optional
+    /// [`AclIndicator`] and the end of input.
+    fn from_sym_with_acl_chars(
+        chars: &mut Chars,
+        mut pos: usize,
+    ) -> Result<(Self, Option<AclIndicator>), ParseError> {
+        let (user, setuid) = Self::class_from_sym(chars, &mut pos, 's')?;
+        let (group, setgid) = Self::class_from_sym(chars, &mut pos, 's')?;
+        let (other, sticky) = Self::class_from_sym(chars, &mut pos, 't')?;
+
+        let indicator = match chars.next() {
+            None => None,
+            Some('+') => Some(AclIndicator::Acl),
+            Some('.') => Some(AclIndicator::SecurityContext),
+            Some(c) => {
+                return Err(ParseError::UnexpectedChar {
+                    pos,
+                    c,
+                    expected: Some(vec!['+', '.']),
+                })
+            }
+        };
+
+        if let Some(c) = chars.next() {
+            return Err(ParseError::UnexpectedChar {
+                pos: pos + 1,
+                c,
+                expected: None,
+            });
+        }
+
+        let mode = Self {
+            user,
+            group,
+            other,
+            setuid,
+            setgid,
+            sticky,
+        };
+        Ok((mode, indicator))
+    }
+
+    /// Parse the symbolic representation of a class (see [`Mode::push_class_sym`]), returning its
+    /// permissions and whether its special bit is set.
+    #[inline]
+    fn class_from_sym(
+        chars: &mut Chars,
+        pos: &mut usize,
+        set: char,
+    ) -> Result<(Perm, bool), ParseError> {
+        let mut next = |options: &[char]| {
+            let c = match chars.next() {
+                None => return Err(ParseError::UnexpectedEoi { pos: *pos }),
+                Some(c) if options.contains(&c) => c,
+                Some(c) => {
+                    return Err(ParseError::UnexpectedChar {
+                        c,
+                        pos: *pos,
+                        expected: Some(options.to_vec()),
+                    })
+                }
+            };
+            *pos += 1;
+            Ok(c)
+        };
+
+        let read = next(&['r', '-'])? == 'r';
+        let write = next(&['w', '-'])? == 'w';
+        let (execute, special) = match next(&['x', set, set.to_ascii_uppercase(), '-'])? {
+            'x' => (true, false),
+            '-' => (false, false),
+            c => (c == set, true),
+        };
+
+        Ok((Perm::new(read, write, execute), special))
+    }
+
+    /// Parse the symbolic representation of a class from bytes, returning `None` if it is
+    /// invalid (see [`Mode::class_from_sym`]).
+    #[inline]
+    fn class_from_sym_opt(bytes: &[u8], set: u8) -> Option<(Perm, bool)> {
+        let (read, write, execute) = match bytes {
+            [r, w, x] => (*r, *w, *x),
+            _ => return None,
+        };
+
+        let read = match read {
+            b'r' => true,
+            b'-' => false,
+            _ => return None,
+        };
+        let write = match write {
+            b'w' => true,
+            b'-' => false,
+            _ => return None,
+        };
+        let (execute, special) = match execute {
+            b'x' => (true, false),
+            b'-' => (false, false),
+            x if x == set => (true, true),
+            x if x == set.to_ascii_uppercase() => (false, true),
+            _ => return None,
+        };
+
+        Some((Perm::new(read, write, execute), special))
+    }
+}
+
+impl Perm {
+    /// Length of the octal representation of a [`Perm`] (see [`Perm::as_num`]).
+    pub const NUM_LEN: usize = 1;
+    /// Length of the full symbolic representation of a [`Perm`] (see [`Perm::as_sym_full`]).
+    pub const SYM_FULL_LEN: usize = 3;
+
+    /// Create a new [`Perm`].
+    #[inline]
+    pub const fn new(read: bool, write: bool, execute: bool) -> Self {
+        Self {
+            read,
+            write,
+            execute,
+        }
+    }
+
+    /// Get the octal representation the [`Perm`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Perm;
+    ///
+    /// let p1 = Perm::new(true, true, false);
+    /// assert_eq!("6", p1.as_num());
+    ///
+    /// let p2 = Perm::new(true, false, true);
+    /// assert_eq!("5", p2.as_num());
+    /// ```
+    #[inline]
+    pub fn as_num(&self) -> String {
+        self.bits().to_string()
+    }
+
+    /// Get the symbolic representation, with ungranted permissions omitted, of the [`Perm`].
+    ///
+    /// Granted permissions always appear in the order `r`, `w`, `x`, so the output is between zero
+    /// and three characters long.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Perm;
+    ///
+    /// let p1 = Perm::new(true, true, false);
+    /// assert_eq!("rw", p1.as_sym());
+    ///
+    /// let p2 = Perm::new(true, false, true);
+    /// assert_eq!("rx", p2.as_sym());
+    /// ```
+    #[inline]
+    pub fn as_sym(&self) -> String {
+        let r = if self.read { "r" } else { "" };
+        let w = if self.write { "w" } else { "" };
+        let x = if self.execute { "x" } else { "" };
+        format!("{}{}{}", r, w, x)
+    }
+
+    /// Get the symbolic representation of the [`Perm`] (see [`Perm::as_sym`]) as a fixed buffer
+    /// and the number of valid bytes at its start, without allocating.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Perm;
+    ///
+    /// let (buf, len) = Perm::new(true, false, true).as_sym_bytes();
+    /// assert_eq!(b"rx", &buf[..len]);
+    ///
+    /// let (_, len) = Perm::new(false, false, false).as_sym_bytes();
+    /// assert_eq!(0, len);
+    /// ```
+    #[inline]
+    pub const fn as_sym_bytes(&self) -> ([u8; 3], usize) {
+        let mut buf = [0; 3];
+        let mut len = 0;
+        if self.read {
+            buf[len] = b'r';
+            len += 1;
+        }
+        if self.write {
+            buf[len] = b'w';
+            len += 1;
+        }
+        if self.execute {
+            buf[len] = b'x';
+            len += 1;
+        }
+        (buf, len)
+    }
+
+    /// Get the symbolic representation, with ungranted permissions as '-', of the [`Perm`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Perm;
+    ///
+    /// let p1 = Perm::new(true, true, false);
+    /// assert_eq!("rw-", p1.as_sym_full());
+    ///
+    /// let p2 = Perm::new(true, false, true);
+    /// assert_eq!("r-x", p2.as_sym_full());
+    /// ```
+    #[inline]
+    pub fn as_sym_full(&self) -> String {
+        let mut sym = String::with_capacity(Self::SYM_FULL_LEN);
+        self.push_sym_full(&mut sym);
+        sym
+    }
+
+    /// Get the octal representation of the [`Perm`] as a static string, without allocating.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::perm::RW;
+    ///
+    /// assert_eq!("6", RW.as_num_static());
+    /// ```
+    #[inline]
+    pub const fn as_num_static(&self) -> &'static str {
+        tables::PERM_NUM[self.bits() as usize]
+    }
+
+    /// Get the symbolic representation, with ungranted permissions omitted, of the [`Perm`] as a
+    /// static string, without allocating.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::perm::RW;
+    ///
+    /// assert_eq!("rw", RW.as_sym_static());
+    /// ```
+    #[inline]
+    pub const fn as_sym_static(&self) -> &'static str {
+        tables::PERM_SYM[self.bits() as usize]
+    }
+
+    /// Get the symbolic representation, with ungranted permissions as '-', of the [`Perm`] as a
+    /// static string, without allocating.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::perm::RW;
+    ///
+    /// assert_eq!("rw-", RW.as_sym_full_static());
+    /// ```
+    #[inline]
+    pub const fn as_sym_full_static(&self) -> &'static str {
+        tables::PERM_SYM_FULL[self.bits() as usize]
+    }
+
+    /// Create a [`Perm`] from its octal form, returning [`ParseError`] if the input is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::{Perm, ParseError};
+    ///
+    /// assert_eq!(Perm::new(true, true, true), Perm::from_num("7").unwrap());
+    /// assert_eq!(Perm::new(true, false, false), Perm::from_num("4").unwrap());
+    ///
+    /// assert_eq!(
+    ///     ParseError::UnexpectedEoi { pos: 0 },
+    ///     Perm::from_num("").unwrap_err()
+    /// );
+    /// assert_eq!(
+    ///     ParseError::UnexpectedChar {
+    ///         pos: 0,
+    ///         c: '8',
+    ///         expected: Some(vec!['0', '1', '2', '3', '4', '5', '6', '7'])
+    ///     },
+    ///     Perm::from_num("8").unwrap_err()
+    /// );
+    /// ```
+    #[inline]
+    pub fn from_num(num: &str) -> Result<Self, ParseError> {
+        let tup = match num {
+            "7" => (true, true, true),
+            "6" => (true, true, false),
+            "5" => (true, false, true),
+            "4" => (true, false, false),
+            "3" => (false, true, true),
+            "2" => (false, true, false),
+            "1" => (false, false, true),
+            "0" => (false, false, false),
+            "" => return Err(ParseError::UnexpectedEoi { pos: 0 }),
+            c => {
+                return Err(ParseError::UnexpectedChar {
+                    c: c.chars().next().unwrap(),
+                    pos: 0,
+                    expected: Some(octal_digits()),
+                })
+            }
+        };
+
+        Ok(tup.into())
+    }
+
+    /// Create a [`Perm`] from a single octal digit, returning [`None`] if the digit is not
+    /// between '0' and '7'.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Perm;
+    ///
+    /// assert_eq!(Some(Perm::new(true, false, true)), Perm::from_octal_digit('5'));
+    /// assert_eq!(None, Perm::from_octal_digit('8'));
+    /// ```
+    #[inline]
+    pub const fn from_octal_digit(c: char) -> Option<Self> {
+        match c {
+            '0'..='7' => Some(Self::from_bits(c as u8 - b'0')),
+            _ => None,
+        }
+    }
+
+    /// Create a [`Perm`] from its symbolic form, returning [`ParseError`] if the input is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::{Perm, ParseError};
+    ///
+    /// assert_eq!(Perm::new(true, true, true), Perm::from_sym_full("rwx").unwrap());
+    /// assert_eq!(Perm::new(true, false, false), Perm::from_sym_full("r--").unwrap());
+    ///
+    /// assert_eq!(
+    ///     ParseError::UnexpectedEoi { pos: 2 },
+    ///     Perm::from_sym_full("rw").unwrap_err()
+    /// );
+    /// assert_eq!(
+    ///     ParseError::UnexpectedChar { pos: 3, c: 'r', expected: None },
+    ///     Perm::from_sym_full("rwxr").unwrap_err()
+    /// );
+    /// ```
+    #[inline]
+    pub fn from_sym_full(sym: &str) -> Result<Self, ParseError> {
+        #[inline]
+        fn process_char(e: char, pos: &mut usize, chars: &mut Chars) -> Result<bool, ParseError> {
+            let r = match chars.next() {
+                None => Err(ParseError::UnexpectedEoi { pos: *pos }),
+                Some(c) if c == e => Ok(true),
+                Some('-') => Ok(false),
+                Some(c) => Err(ParseError::UnexpectedChar {
+                    c,
+                    pos: *pos,
+                    expected: Some(vec![e, '-']),
+                }),
+            };
+            *pos += 1;
+            r
+        }
+
+        let mut chars = sym.chars();
+
+        let mut pos = 0;
+        let read = process_char('r', &mut pos, &mut chars)?;
+        let write = process_char('w', &mut pos, &mut chars)?;
+        let execute = process_char('x', &mut pos, &mut chars)?;
+
+        if let Some(c) = chars.next() {
+            Err(ParseError::UnexpectedChar {
+                c,
+                pos,
+                expected: None,
+            })
+        } else {
+            Ok(Self {
+                read,
+                write,
+                execute,
+            })
+        }
+    }
+
+    /// Create a [`Perm`] from its octal form, returning `None` if the input is invalid. This is
+    /// equivalent to [`Perm::from_num`], but avoids constructing a [`ParseError`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Perm;
+    ///
+    /// assert_eq!(Perm::from_num("5").ok(), Perm::from_num_opt("5"));
+    /// assert_eq!(None, Perm::from_num_opt("8"));
+    /// assert_eq!(None, Perm::from_num_opt("55"));
+    /// ```
+    #[inline]
+    pub fn from_num_opt(num: &str) -> Option<Self> {
+        match num.as_bytes() {
+            [b] => Self::from_octal_digit(*b as char),
+            _ => None,
+        }
+    }
+
+    /// Create a [`Perm`] from its full symbolic form, returning `None` if the input is invalid.
+    /// This is equivalent to [`Perm::from_sym_full`], but avoids constructing a [`ParseError`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Perm;
+    ///
+    /// assert_eq!(Perm::from_sym_full("r-x").ok(), Perm::from_sym_full_opt("r-x"));
+    /// assert_eq!(None, Perm::from_sym_full_opt("x-r"));
+    /// assert_eq!(None, Perm::from_sym_full_opt("rw"));
+    /// ```
+    #[inline]
+    pub fn from_sym_full_opt(sym: &str) -> Option<Self> {
+        #[inline]
+        fn flag(b: u8, e: u8) -> Option<bool> {
+            match b {
+                b'-' => Some(false),
+                b if b == e => Some(true),
+                _ => None,
+            }
+        }
+
+        match sym.as_bytes() {
+            [r, w, x] => Some(Self {
+                read: flag(*r, b'r')?,
+                write: flag(*w, b'w')?,
+                execute: flag(*x, b'x')?,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Create a [`Perm`] from a set of granted permission letters (`r`, `w`, and `x`), returning
+    /// [`ParseError`] if the input is invalid.
+    ///
+    /// If `require_order` is `false`, the letters may appear in any order and duplicates are
+    /// ignored. Otherwise, each letter may appear at most once and in canonical order, as produced
+    /// by [`Perm::as_sym`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::{Perm, ParseError};
+    ///
+    /// assert_eq!(Perm::new(true, true, true), Perm::from_letters("xwr", false).unwrap());
+    /// assert_eq!(Perm::new(true, false, true), Perm::from_letters("rxr", false).unwrap());
+    /// assert_eq!(Perm::new(true, false, true), Perm::from_letters("rx", true).unwrap());
+    /// assert_eq!(Perm::new(false, false, false), Perm::from_letters("", true).unwrap());
+    ///
+    /// assert_eq!(
+    ///     ParseError::UnexpectedChar { pos: 1, c: 'w', expected: None },
+    ///     Perm::from_letters("xwr", true).unwrap_err()
+    /// );
+    /// assert_eq!(
+    ///     ParseError::UnexpectedChar { pos: 1, c: 'r', expected: Some(vec!['w', 'x']) },
+    ///     Perm::from_letters("rr", true).unwrap_err()
+    /// );
+    /// assert_eq!(
+    ///     ParseError::UnexpectedChar { pos: 2, c: '-', expected: Some(vec!['r', 'w', 'x']) },
+    ///     Perm::from_letters("rw-", false).unwrap_err()
+    /// );
+    /// ```
+    pub fn from_letters(letters: &str, require_order: bool) -> Result<Self, ParseError> {
+        const ORDER: [char; 3] = ['r', 'w', 'x'];
+
+        let mut perm = Self::new(false, false, false);
+        // Index of the first letter in ORDER still permitted when require_order is set.
+        let mut next = 0;
+        for (pos, c) in letters.chars().enumerate() {
+            let idx = match ORDER.iter().position(|&l| l == c) {
+                Some(idx) if !require_order || idx >= next => idx,
+                _ => {
+                    let expected = if !require_order {
+                        Some(ORDER.to_vec())
+                    } else if next < ORDER.len() {
+                        Some(ORDER[next..].to_vec())
+                    } else {
+                        None
+                    };
+                    return Err(ParseError::UnexpectedChar { pos, c, expected });
+                }
+            };
+
+            match idx {
+                0 => perm.read = true,
+                1 => perm.write = true,
+                _ => perm.execute = true,
+            }
+            next = idx + 1;
+        }
+
+        Ok(perm)
+    }
+
+    /// Create a [`Perm`] from the start of a string, in either octal or full symbolic form,
+    /// returning the [`Perm`] and the number of characters consumed. Input beginning with a digit
+    /// is parsed as octal, and all other input as symbolic.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::{Perm, ParseError};
+    ///
+    /// assert_eq!((Perm::new(true, false, true), 1), Perm::parse_prefix("55").unwrap());
+    /// assert_eq!((Perm::new(true, true, false), 3), Perm::parse_prefix("rw-r--").unwrap());
+    ///
+    /// assert_eq!(
+    ///     ParseError::UnexpectedEoi { pos: 2 },
+    ///     Perm::parse_prefix("rw").unwrap_err()
+    /// );
+    /// ```
+    #[inline]
+    pub fn parse_prefix(s: &str) -> Result<(Self, usize), ParseError> {
+        if s.starts_with(|c: char| c.is_ascii_digit()) {
+            Ok((Self::from_num(prefix(s, Self::NUM_LEN))?, Self::NUM_LEN))
+        } else {
+            Ok((
+                Self::from_sym_full(prefix(s, Self::SYM_FULL_LEN))?,
+                Self::SYM_FULL_LEN,
+            ))
+        }
+    }
+
+    /// Compute the diff ([`PermDiff`]) between two [`Perm`]s.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::{Perm, PermDiff, DiffOp::*};
+    ///
+    /// let a = Perm::from_num("7").unwrap();
+    /// let b = Perm::from_num("6").unwrap();
+    ///
+    /// assert_eq!(
+    ///     PermDiff { read: Same, write: Same, execute: Minus },
+    ///     a.diff(&b)
+    /// );
+    /// ```
+    #[inline]
+    pub const fn diff(&self, other: &Self) -> PermDiff {
+        PermDiff {
+            read: bool_diff(self.read, other.read),
+            write: bool_diff(self.write, other.write),
+            execute: bool_diff(self.execute, other.execute),
+        }
+    }
+
+    /// Apply a diff ([`PermDiff`]) to the [`Perm`], granting the permissions marked
+    /// [`DiffOp::Plus`] and revoking those marked [`DiffOp::Minus`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::{Perm, PermDiff, DiffOp::*};
+    ///
+    /// let diff = PermDiff { read: Same, write: Plus, execute: Minus };
+    /// assert_eq!(Perm::from_num("6").unwrap(), Perm::from_num("5").unwrap().apply(&diff));
+    /// assert_eq!(Perm::from_num("2").unwrap(), Perm::from_num("0").unwrap().apply(&diff));
+    /// ```
+    #[inline]
+    pub const fn apply(&self, diff: &PermDiff) -> Self {
+        Self {
+            read: bool_apply(self.read, &diff.read),
+            write: bool_apply(self.write, &diff.write),
+            execute: bool_apply(self.execute, &diff.execute),
+        }
+    }
+
+    /// Count the number of permissions granted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::perm::*;
+    ///
+    /// assert_eq!(3, RWX.count());
+    /// assert_eq!(2, RX.count());
+    /// assert_eq!(0, EMPTY.count());
+    /// ```
+    #[inline]
+    pub const fn count(&self) -> u32 {
+        self.read as u32 + self.write as u32 + self.execute as u32
+    }
+
+    /// Get the three [`Perm`]s that differ from this one by exactly one permission, obtained by
+    /// toggling read, write, and execute, in that order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::perm::*;
+    ///
+    /// assert_eq!(vec![WX, RX, RW], RWX.neighbors().collect::<Vec<_>>());
+    /// assert_eq!(vec![RX, WX, EMPTY], X.neighbors().collect::<Vec<_>>());
+    /// ```
+    #[inline]
+    pub fn neighbors(&self) -> impl Iterator<Item = Perm> {
+        IntoIterator::into_iter([
+            Self::new(!self.read, self.write, self.execute),
+            Self::new(self.read, !self.write, self.execute),
+            Self::new(self.read, self.write, !self.execute),
+        ])
+    }
+
+    /// Swap the read and write permissions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::perm::*;
+    ///
+    /// assert_eq!(WX, RX.swap_read_write());
+    /// assert_eq!(RW, RW.swap_read_write());
+    /// ```
+    #[inline]
+    pub const fn swap_read_write(&self) -> Self {
+        Self::new(self.write, self.read, self.execute)
+    }
+
+    /// Swap the read and execute permissions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::perm::*;
+    ///
+    /// assert_eq!(X, R.swap_read_execute());
+    /// assert_eq!(RX, RX.swap_read_execute());
+    /// ```
+    #[inline]
+    pub const fn swap_read_execute(&self) -> Self {
+        Self::new(self.execute, self.write, self.read)
+    }
+
+    /// Rotate the permissions, so that read moves to write, write to execute, and execute to
+    /// read. Three rotations return the original [`Perm`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::perm::*;
+    ///
+    /// assert_eq!(W, R.rotate_bits());
+    /// assert_eq!(X, W.rotate_bits());
+    /// assert_eq!(WX, RW.rotate_bits());
+    /// ```
+    #[inline]
+    pub const fn rotate_bits(&self) -> Self {
+        Self::new(self.execute, self.read, self.write)
+    }
+
+    /// Clamp the [`Perm`] so that it grants at least the permissions in `min` and at most the
+    /// permissions in `max`, i.e. the union with `min` intersected with `max`. Permissions in
+    /// `min` but not in `max` are not granted. Unlike [`Ord::clamp`], this is not based on the
+    /// ordering of permissions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::perm::*;
+    ///
+    /// assert_eq!(RW, R.clamp(&W, &RW));
+    /// assert_eq!(RX, RWX.clamp(&EMPTY, &RX));
+    /// assert_eq!(R, X.clamp(&R, &RW));
+    /// ```
+    #[inline]
+    pub const fn clamp(self, min: &Self, max: &Self) -> Self {
+        Self {
+            read: (self.read || min.read) && max.read,
+            write: (self.write || min.write) && max.write,
+            execute: (self.execute || min.execute) && max.execute,
+        }
+    }
+
+    /// Check whether every permission granted by `other` is also granted by this [`Perm`]. Unlike
+    /// the ordering of permissions, this is a subset relation: neither of `RW` and `RX` contains
+    /// the other, although `RW > RX`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::perm::*;
+    ///
+    /// assert!(RWX.contains(&RW));
+    /// assert!(RW.contains(&RW));
+    /// assert!(RW.contains(&EMPTY));
+    /// assert!(!RW.contains(&RX));
+    /// assert!(!RX.contains(&RW));
+    /// ```
+    #[inline]
+    pub const fn contains(&self, other: &Self) -> bool {
+        (self.read || !other.read)
+            && (self.write || !other.write)
+            && (self.execute || !other.execute)
+    }
+
+    /// Set a single permission.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::{perm::*, PermBit};
+    ///
+    /// let mut p = R;
+    /// p.set(PermBit::Execute, true);
+    /// assert_eq!(RX, p);
+    /// p.set(PermBit::Read, false);
+    /// assert_eq!(X, p);
+    /// ```
+    #[inline]
+    pub fn set(&mut self, bit: PermBit, value: bool) {
+        *self.bit_mut(bit) = value;
+    }
+
+    /// Toggle a single permission.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::{perm::*, PermBit};
+    ///
+    /// let mut p = RW;
+    /// p.toggle(PermBit::Write);
+    /// assert_eq!(R, p);
+    /// p.toggle(PermBit::Write);
+    /// assert_eq!(RW, p);
+    /// ```
+    #[inline]
+    pub fn toggle(&mut self, bit: PermBit) {
+        let b = self.bit_mut(bit);
+        *b = !*b;
+    }
+
+    /// Set the read permission.
+    #[inline]
+    pub fn set_read(&mut self, value: bool) {
+        self.set(PermBit::Read, value);
+    }
+
+    /// Set the write permission.
+    #[inline]
+    pub fn set_write(&mut self, value: bool) {
+        self.set(PermBit::Write, value);
+    }
+
+    /// Set the execute permission.
+    #[inline]
+    pub fn set_execute(&mut self, value: bool) {
+        self.set(PermBit::Execute, value);
+    }
+
+    /// Toggle the read permission.
+    #[inline]
+    pub fn toggle_read(&mut self) {
+        self.toggle(PermBit::Read);
+    }
+
+    /// Toggle the write permission.
+    #[inline]
+    pub fn toggle_write(&mut self) {
+        self.toggle(PermBit::Write);
+    }
+
+    /// Toggle the execute permission.
+    #[inline]
+    pub fn toggle_execute(&mut self) {
+        self.toggle(PermBit::Execute);
+    }
+}
+
+impl Perm {
+    /// Get a mutable reference to a single permission.
+    #[inline]
+    fn bit_mut(&mut self, bit: PermBit) -> &mut bool {
+        match bit {
+            PermBit::Read => &mut self.read,
+            PermBit::Write => &mut self.write,
+            PermBit::Execute => &mut self.execute,
+        }
+    }
+
+    /// Append the full symbolic representation of the [`Perm`] to a buffer.
+    #[inline]
+    fn push_sym_full(&self, buf: &mut String) {
+        buf.push(if self.read { 'r' } else { '-' });
+        buf.push(if self.write { 'w' } else { '-' });
+        buf.push(if self.execute { 'x' } else { '-' });
+    }
+
+    /// Get the octal value of the [`Perm`].
+    #[inline]
+    const fn bits(&self) -> u8 {
+        (if self.read { 0o4 } else { 0 })
+            + (if self.write { 0o2 } else { 0 })
+            + (if self.execute { 0o1 } else { 0 })
+    }
+
+    /// Create a [`Perm`] from the low three bits of an octal value.
+    #[inline]
+    const fn from_bits(bits: u8) -> Self {
+        Self {
+            read: bits & 0o4 != 0,
+            write: bits & 0o2 != 0,
+            execute: bits & 0o1 != 0,
+        }
+    }
+}
+
+impl ModeMask {
+    /// Create a [`ModeMask`] from either a complete mode (see [`Mode::parse`]), which affects
+    /// every class and the special bits, or a class letter (`u`, `g`, or `o`) followed by a
+    /// single octal digit (e.g. `u7`), which affects only that class. Returns [`ParseError`] if
+    /// the input is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::{Mode, ModeMask, ParseError, perm::*};
+    ///
+    /// assert_eq!(
+    ///     ModeMask {
+    ///         user: Some(RWX),
+    ///         group: None,
+    ///         other: None,
+    ///         setuid: None,
+    ///         setgid: None,
+    ///         sticky: None,
+    ///     },
+    ///     ModeMask::parse("u7").unwrap()
+    /// );
+    /// assert_eq!(
+    ///     ModeMask::from(Mode::from_num("755").unwrap()),
+    ///     ModeMask::parse("755").unwrap()
+    /// );
+    ///
+    /// assert_eq!(
+    ///     ParseError::UnexpectedChar { pos: 2, c: '5', expected: None },
+    ///     ModeMask::parse("u75").unwrap_err()
+    /// );
+    /// ```
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        let mut chars = input.chars();
+
+        let mut mask = Self {
+            user: None,
+            group: None,
+            other: None,
+            setuid: None,
+            setgid: None,
+            sticky: None,
+        };
+        let class = match chars.next() {
+            Some('u') => &mut mask.user,
+            Some('g') => &mut mask.group,
+            Some('o') => &mut mask.other,
+            _ => return Mode::parse(input).map(Self::from),
+        };
+
+        *class = match chars.next() {
+            Some(c) => {
+                Some(
+                    Perm::from_octal_digit(c).ok_or_else(|| ParseError::UnexpectedChar {
+                        pos: 1,
+                        c,
+                        expected: Some(octal_digits()),
+                    })?,
+                )
+            }
+            None => return Err(ParseError::UnexpectedEoi { pos: 1 }),
+        };
+
+        if let Some(c) = chars.next() {
+            Err(ParseError::UnexpectedChar {
+                pos: 2,
+                c,
+                expected: None,
+            })
+        } else {
+            Ok(mask)
+        }
+    }
+
+    /// Apply the [`ModeMask`] to a [`Mode`], replacing the permissions of the affected classes
+    /// and the affected special bits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::{Mode, ModeMask};
+    ///
+    /// let m = Mode::from_num("644").unwrap();
+    /// assert_eq!("744", ModeMask::parse("u7").unwrap().apply(&m).as_num());
+    /// assert_eq!("614", ModeMask::parse("g1").unwrap().apply(&m).as_num());
+    /// assert_eq!("755", ModeMask::parse("755").unwrap().apply(&m).as_num());
+    /// assert_eq!("4755", ModeMask::parse("4755").unwrap().apply(&m).as_num());
+    /// ```
+    #[inline]
+    pub fn apply(&self, mode: &Mode) -> Mode {
+        Mode {
+            user: self.user.unwrap_or(mode.user),
+            group: self.group.unwrap_or(mode.group),
+            other: self.other.unwrap_or(mode.other),
+            setuid: self.setuid.unwrap_or(mode.setuid),
+            setgid: self.setgid.unwrap_or(mode.setgid),
+            sticky: self.sticky.unwrap_or(mode.sticky),
+        }
+    }
+}
+
+impl FileType {
+    /// Get the [`FileType`] encoded in the file type bits (`S_IFMT`) of a raw mode, or [`None`]
+    /// if they do not encode a known type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::FileType;
+    ///
+    /// assert_eq!(Some(FileType::Directory), FileType::from_raw(0o40755));
+    /// assert_eq!(Some(FileType::Regular), FileType::from_raw(0o100644));
+    /// assert_eq!(None, FileType::from_raw(0o644));
+    /// ```
+    #[inline]
+    pub const fn from_raw(mode: u32) -> Option<Self> {
+        match mode & 0o170000 {
+            0o100000 => Some(Self::Regular),
+            0o040000 => Some(Self::Directory),
+            0o120000 => Some(Self::Symlink),
+            0o020000 => Some(Self::CharDevice),
+            0o060000 => Some(Self::BlockDevice),
+            0o010000 => Some(Self::Fifo),
+            0o140000 => Some(Self::Socket),
+            _ => None,
+        }
+    }
+
+    /// Get the character that represents the [`FileType`] in the output of `ls -l`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::FileType;
+    ///
+    /// assert_eq!('-', FileType::Regular.as_char());
+    /// assert_eq!('d', FileType::Directory.as_char());
+    /// ```
+    #[inline]
+    pub const fn as_char(&self) -> char {
+        match self {
+            Self::Regular => '-',
+            Self::Directory => 'd',
+            Self::Symlink => 'l',
+            Self::CharDevice => 'c',
+            Self::BlockDevice => 'b',
+            Self::Fifo => 'p',
+            Self::Socket => 's',
+        }
+    }
+}
+
+impl From<Mode> for ModeMask {
+    /// Create a [`ModeMask`] that affects every class and the special bits.
+    #[inline]
+    fn from(mode: Mode) -> Self {
+        Self {
+            user: Some(mode.user),
+            group: Some(mode.group),
+            other: Some(mode.other),
+            setuid: Some(mode.setuid),
+            setgid: Some(mode.setgid),
+            sticky: Some(mode.sticky),
+        }
+    }
+}
+
+impl From<u32> for Mode {
+    /// Create a [`Mode`] from a raw numeric mode (e.g. from
+    /// [`PermissionsExt::mode`](std::os::unix::fs::PermissionsExt::mode)), ignoring any bits
+    /// above the special bits, such as the file type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// assert_eq!(Mode::from_num("755").unwrap(), Mode::from(0o755));
+    /// assert_eq!(Mode::from_num("4755").unwrap(), Mode::from(0o104755));
+    /// ```
+    #[inline]
+    fn from(mode: u32) -> Self {
+        Self::from_bits((mode & 0o7777) as u16)
+    }
+}
+
+impl From<Mode> for u32 {
+    /// Get the raw numeric mode of a [`Mode`], including the special bits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// assert_eq!(0o755, u32::from(Mode::from_num("755").unwrap()));
+    /// assert_eq!(0o1777, u32::from(Mode::from_num("1777").unwrap()));
+    /// ```
+    #[inline]
+    fn from(mode: Mode) -> Self {
+        mode.bits() as u32
+    }
+}
+
+impl From<(bool, bool, bool)> for Perm {
+    /// Create a [`Perm`] from a tuple of boolean with form `(user, group, other)`.
+    #[inline]
+    fn from(tup: (bool, bool, bool)) -> Self {
+        Self {
+            read: tup.0,
+            write: tup.1,
+            execute: tup.2,
+        }
+    }
+}
+
+/// Implement a binary bitwise operator for every combination of owned and borrowed [`Perm`]
+/// operands, operating on each permission separately.
+macro_rules! impl_perm_bitop {
+    ($trait:ident, $method:ident, $op:tt) => {
+        impl $trait<&Perm> for &Perm {
+            type Output = Perm;
+
+            #[inline]
+            fn $method(self, rhs: &Perm) -> Perm {
+                Perm::from_bits(self.bits() $op rhs.bits())
+            }
+        }
+
+        impl $trait<Perm> for &Perm {
+            type Output = Perm;
+
+            #[inline]
+            fn $method(self, rhs: Perm) -> Perm {
+                self.$method(&rhs)
+            }
+        }
+
+        impl $trait<&Perm> for Perm {
+            type Output = Perm;
+
+            #[inline]
+            fn $method(self, rhs: &Perm) -> Perm {
+                (&self).$method(rhs)
+            }
+        }
+
+        impl $trait<Perm> for Perm {
+            type Output = Perm;
+
+            #[inline]
+            fn $method(self, rhs: Perm) -> Perm {
+                (&self).$method(&rhs)
+            }
+        }
+    };
+}
+
+impl_perm_bitop!(BitOr, bitor, |);
+impl_perm_bitop!(BitAnd, bitand, &);
+impl_perm_bitop!(BitXor, bitxor, ^);
+
+impl Not for &Perm {
+    type Output = Perm;
+
+    /// Get the permissions that are not granted by the [`Perm`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::perm::*;
+    ///
+    /// assert_eq!(WX, !R);
+    /// assert_eq!(RX, RWX & !W);
+    /// assert_eq!(RW, !&X);
+    /// ```
+    #[inline]
+    fn not(self) -> Perm {
+        Perm::from_bits(!self.bits())
+    }
+}
+
+impl Not for Perm {
+    type Output = Perm;
+
+    #[inline]
+    fn not(self) -> Perm {
+        !&self
+    }
+}
+
+impl Add<ModeDiff> for Mode {
+    type Output = Self;
+
+    /// Apply a diff to the [`Mode`] (see [`Mode::apply`]).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// let diff = Mode::from_num("644").unwrap().diff(&Mode::from_num("755").unwrap());
+    /// assert_eq!("755", (Mode::from_num("644").unwrap() + diff).as_num());
+    /// ```
+    #[inline]
+    fn add(self, diff: ModeDiff) -> Self {
+        self.apply(&diff)
+    }
+}
+
+impl AddAssign<ModeDiff> for Mode {
+    /// Apply a diff to the [`Mode`] in place (see [`Mode::apply`]).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// let mut m = Mode::from_num("644").unwrap();
+    /// m += m.diff(&Mode::from_num("600").unwrap());
+    /// assert_eq!("600", m.as_num());
+    /// ```
+    #[inline]
+    fn add_assign(&mut self, diff: ModeDiff) {
+        *self = self.apply(&diff);
+    }
+}
+
+impl Add<PermDiff> for Perm {
+    type Output = Self;
+
+    /// Apply a diff to the [`Perm`] (see [`Perm::apply`]).
+    #[inline]
+    fn add(self, diff: PermDiff) -> Self {
+        self.apply(&diff)
+    }
+}
+
+impl AddAssign<PermDiff> for Perm {
+    /// Apply a diff to the [`Perm`] in place (see [`Perm::apply`]).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::perm::*;
+    ///
+    /// let mut p = RW;
+    /// p += RW.diff(&RX);
+    /// assert_eq!(RX, p);
+    /// ```
+    #[inline]
+    fn add_assign(&mut self, diff: PermDiff) {
+        *self = self.apply(&diff);
+    }
+}
+
+impl fmt::Display for Mode {
+    /// Format the [`Mode`] in its symbolic form (see [`Mode::as_sym`]), or in its octal form
+    /// (see [`Mode::as_num`]) with the alternate flag (`{:#}`). The width, fill, and alignment of
+    /// the formatter are respected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// let m = Mode::from_num("755").unwrap();
+    /// assert_eq!("rwxr-xr-x", format!("{}", m));
+    /// assert_eq!("755", format!("{:#}", m));
+    /// assert_eq!("rwxr-xr-x", m.to_string());
+    /// assert_eq!(" 755", format!("{:>#4}", m));
+    /// ```
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            f.pad(self.as_num_static())
+        } else {
+            f.pad(self.as_sym_static())
+        }
+    }
+}
+
+impl fmt::Display for Perm {
+    /// Format the [`Perm`] in its full symbolic form (see [`Perm::as_sym_full`]), or in its octal
+    /// form (see [`Perm::as_num`]) with the alternate flag (`{:#}`). The width, fill, and
+    /// alignment of the formatter are respected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::perm::RW;
+    ///
+    /// assert_eq!("rw-", format!("{}", RW));
+    /// assert_eq!("6", format!("{:#}", RW));
+    /// assert_eq!("rw-  ", format!("{:<5}", RW));
+    /// assert_eq!("  rw-", format!("{:>5}", RW));
+    /// assert_eq!("*rw-*", format!("{:*^5}", RW));
+    /// ```
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            f.pad(self.as_num_static())
+        } else {
+            f.pad(self.as_sym_full_static())
+        }
+    }
+}
+
+/// [`Mode`] is serialized as its octal form (e.g. `"755"`), and deserialized from either its octal
+/// or symbolic form (see [`Mode::parse`]).
+#[cfg(feature = "serde")]
+impl serde::Serialize for Mode {
+    #[inline]
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_num_static())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Mode {
+    #[inline]
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_str(StrVisitor("a mode in octal or symbolic form", Self::parse))
+    }
+}
+
+/// [`Perm`] is serialized as its octal form (e.g. `"5"`), and deserialized from either its octal
+/// or full symbolic form. See [`StructuredPerm`] for serialization as a structure of flags.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Perm {
+    #[inline]
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_num_static())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Perm {
+    #[inline]
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_str(StrVisitor(
+            "a permission in octal or full symbolic form",
+            |s| {
+                if s.len() == Self::NUM_LEN {
+                    Self::from_num(s)
+                } else {
+                    Self::from_sym_full(s)
+                }
+            },
+        ))
+    }
+}
+
+/// Visitor that parses a string with the given function, described by the given expectation.
+#[cfg(feature = "serde")]
+struct StrVisitor<T>(&'static str, fn(&str) -> Result<T, ParseError>);
+
+#[cfg(feature = "serde")]
+impl<'de, T> serde::de::Visitor<'de> for StrVisitor<T> {
+    type Value = T;
+
+    #[inline]
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+
+    #[inline]
+    fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<T, E> {
+        (self.1)(v).map_err(E::custom)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<Perm> for StructuredPerm {
+    #[inline]
+    fn from(perm: Perm) -> Self {
+        Self(perm)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<StructuredPerm> for Perm {
+    #[inline]
+    fn from(perm: StructuredPerm) -> Self {
+        perm.0
+    }
+}
+
+/// The diff between two Modes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ModeDiff {
+    pub user: PermDiff,
+    pub group: PermDiff,
+    pub other: PermDiff,
+    pub setuid: DiffOp,
+    pub setgid: DiffOp,
+    pub sticky: DiffOp,
+}
+
+/// The diff between two Perms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PermDiff {
+    pub read: DiffOp,
+    pub write: DiffOp,
+    pub execute: DiffOp,
+}
+
+/// A single permission change in a [`ModeDiff`], identified by its class and permission letter
+/// (`r`, `w`, or `x`), or by the class and letter of a special bit (`s` for *setuid* and
+/// *setgid*, `t` for *sticky*).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    /// The permission was granted.
+    Added(Class, char),
+    /// The permission was revoked.
+    Removed(Class, char),
+}
+
+impl ModeDiff {
+    /// Get the individual changes of the diff, ordered by class (user, group, other), then by
+    /// permission (read, write, execute), with the class's special bit last.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::{Change, Class, Mode};
+    ///
+    /// let a = Mode::from_num("640").unwrap();
+    /// let b = Mode::from_num("604").unwrap();
+    ///
+    /// assert_eq!(
+    ///     vec![Change::Removed(Class::Group, 'r'), Change::Added(Class::Other, 'r')],
+    ///     a.diff(&b).changes()
+    /// );
+    /// ```
+    pub fn changes(&self) -> Vec<Change> {
+        let mut changes = Vec::new();
+        for (class, diff, special) in [
+            (Class::User, &self.user, ('s', &self.setuid)),
+            (Class::Group, &self.group, ('s', &self.setgid)),
+            (Class::Other, &self.other, ('t', &self.sticky)),
+        ] {
+            for (c, op) in [
+                ('r', &diff.read),
+                ('w', &diff.write),
+                ('x', &diff.execute),
+                special,
+            ] {
+                match op {
+                    DiffOp::Plus => changes.push(Change::Added(class, c)),
+                    DiffOp::Minus => changes.push(Change::Removed(class, c)),
+                    DiffOp::Same => {}
+                }
+            }
+        }
+
+        changes
+    }
+
+    /// Split the diff into a [`Mode`] of the permissions and special bits added ([`DiffOp::Plus`])
+    /// and a [`Mode`] of those removed ([`DiffOp::Minus`]), so that they can be applied
+    /// separately.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// let a = Mode::from_num("640").unwrap();
+    /// let b = Mode::from_num("705").unwrap();
+    ///
+    /// let (adds, removes) = a.diff(&b).split();
+    /// assert_eq!("105", adds.as_num());
+    /// assert_eq!("040", removes.as_num());
+    ///
+    /// let (adds, removes) = Mode::from_num("4755").unwrap().diff(&a).split();
+    /// assert_eq!("000", adds.as_num());
+    /// assert_eq!("4115", removes.as_num());
+    /// ```
+    #[inline]
+    pub const fn split(&self) -> (Mode, Mode) {
+        let (user_adds, user_removes) = self.user.split();
+        let (group_adds, group_removes) = self.group.split();
+        let (other_adds, other_removes) = self.other.split();
+        (
+            Mode {
+                user: user_adds,
+                group: group_adds,
+                other: other_adds,
+                setuid: matches!(self.setuid, DiffOp::Plus),
+                setgid: matches!(self.setgid, DiffOp::Plus),
+                sticky: matches!(self.sticky, DiffOp::Plus),
+            },
+            Mode {
+                user: user_removes,
+                group: group_removes,
+                other: other_removes,
+                setuid: matches!(self.setuid, DiffOp::Minus),
+                setgid: matches!(self.setgid, DiffOp::Minus),
+                sticky: matches!(self.sticky, DiffOp::Minus),
+            },
+        )
+    }
+}
+
+impl PermDiff {
+    /// Split the diff into a [`Perm`] of the permissions added ([`DiffOp::Plus`]) and a [`Perm`]
+    /// of the permissions removed ([`DiffOp::Minus`]).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Perm;
+    ///
+    /// let a = Perm::from_num("6").unwrap();
+    /// let b = Perm::from_num("5").unwrap();
+    ///
+    /// let (adds, removes) = a.diff(&b).split();
+    /// assert_eq!("1", adds.as_num());
+    /// assert_eq!("2", removes.as_num());
+    /// ```
+    #[inline]
+    pub const fn split(&self) -> (Perm, Perm) {
+        (
+            Perm::new(
+                matches!(self.read, DiffOp::Plus),
+                matches!(self.write, DiffOp::Plus),
+                matches!(self.execute, DiffOp::Plus),
+            ),
+            Perm::new(
+                matches!(self.read, DiffOp::Minus),
+                matches!(self.write, DiffOp::Minus),
+                matches!(self.execute, DiffOp::Minus),
+            ),
+        )
+    }
+
+    /// Get the [`Perm`] to add with `+` and the [`Perm`] to remove with `-` to make the change of
+    /// the diff, the same as [`PermDiff::split`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::perm::*;
+    ///
+    /// assert_eq!((EMPTY, X), RWX.diff(&RW).to_add_remove());
+    /// assert_eq!((X, W), RW.diff(&RX).to_add_remove());
+    /// ```
+    #[inline]
+    pub const fn to_add_remove(&self) -> (Perm, Perm) {
+        self.split()
+    }
+}
+
+impl fmt::Display for ModeDiff {
+    /// Format the diff as chmod-style operations grouped by class (e.g. `u+x,g-w`), with the
+    /// special bits written as `s` for the user and group classes and `t` for the other class.
+    /// Classes without any changes are omitted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// let a = Mode::from_num("777").unwrap();
+    /// let b = Mode::from_num("644").unwrap();
+    ///
+    /// assert_eq!("u-x,g-wx,o-wx", format!("{}", a.diff(&b)));
+    /// assert_eq!("g+w", format!("{}", b.diff(&Mode::from_num("664").unwrap())));
+    /// assert_eq!("", format!("{}", a.diff(&a)));
+    ///
+    /// let c = Mode::from_num("4755").unwrap();
+    /// assert_eq!("u-s", format!("{}", c.diff(&Mode::from_num("755").unwrap())));
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let clauses: Vec<String> = [
+            ('u', &self.user, ('s', &self.setuid)),
+            ('g', &self.group, ('s', &self.setgid)),
+            ('o', &self.other, ('t', &self.sticky)),
+        ]
+        .iter()
+        .map(|(class, diff, special)| {
+            let bits = [
+                ('r', &diff.read),
+                ('w', &diff.write),
+                ('x', &diff.execute),
+                *special,
+            ];
+            (class, diff_ops(&bits))
+        })
+        .filter(|(_, ops)| !ops.is_empty())
+        .map(|(class, ops)| format!("{}{}", class, ops))
+        .collect();
+
+        f.pad(&clauses.join(","))
+    }
+}
+
+impl fmt::Display for PermDiff {
+    /// Format the diff as chmod-style operations (e.g. `+x-w`), with additions preceding
+    /// removals. Unchanged permissions are omitted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Perm;
+    ///
+    /// let a = Perm::from_num("6").unwrap();
+    /// let b = Perm::from_num("5").unwrap();
+    ///
+    /// assert_eq!("+x-w", format!("{}", a.diff(&b)));
+    /// assert_eq!("  +x-w", format!("{:>6}", a.diff(&b)));
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(&diff_ops(&[
+            ('r', &self.read),
+            ('w', &self.write),
+            ('x', &self.execute),
+        ]))
+    }
+}
+
+/// Enum for diff between two values.
+///
+/// Values are ordered by their effect on the permission, as if they were -1, 0, and +1:
+/// `Minus < Same < Plus`.
+///
+/// # Examples
+///
+/// ```
+/// use cchmod::DiffOp::*;
+///
+/// assert!(Minus < Same);
+/// assert!(Same < Plus);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum DiffOp {
+    /// The permission was revoked.
+    Minus,
+    /// The permission was unchanged.
+    Same,
+    /// The permission was granted.
+    Plus,
+}
+
+impl DiffOp {
+    /// Check whether the [`DiffOp`] represents a change (i.e. is not [`DiffOp::Same`]).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::DiffOp::*;
+    ///
+    /// assert!(Plus.is_change());
+    /// assert!(Minus.is_change());
+    /// assert!(!Same.is_change());
+    /// ```
+    #[inline]
+    pub const fn is_change(&self) -> bool {
+        !matches!(self, Self::Same)
+    }
+}
+
+/// Get the prefix of a string containing at most `n` characters.
+#[inline]
+fn prefix(s: &str, n: usize) -> &str {
+    s.char_indices().nth(n).map_or(s, |(i, _)| &s[..i])
+}
+
+/// Format lettered diff operations as chmod-style operations (e.g. `+x-w`), with additions
+/// preceding removals.
+fn diff_ops(bits: &[(char, &DiffOp)]) -> String {
+    let mut ops = String::new();
+    for (sign, op) in [('+', DiffOp::Plus), ('-', DiffOp::Minus)].iter() {
+        let letters: String = bits
+            .iter()
+            .filter(|(_, o)| *o == op)
+            .map(|(c, _)| c)
+            .collect();
+        if !letters.is_empty() {
+            ops.push(*sign);
+            ops.push_str(&letters);
+        }
+    }
+
+    ops
+}
+
+#[inline]
+fn octal_digits() -> Vec<char> {
+    ('0'..='7').collect()
+}
+
+#[inline]
+const fn bool_diff(a: bool, b: bool) -> DiffOp {
+    use DiffOp::*;
+
+    if a ^ b {
+        if a {
+            Minus
+        } else {
+            Plus
+        }
+    } else {
+        Same
+    }
+}
+
+#[inline]
+const fn bool_apply(b: bool, op: &DiffOp) -> bool {
+    match op {
+        DiffOp::Plus => true,
+        DiffOp::Minus => false,
+        DiffOp::Same => b,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    macro_rules! perm {
+        ($r:expr, $w:expr, $x:expr) => {
+            Perm::new($r, $w, $x)
+        };
+    }
+
+    macro_rules! mode {
+        ($u:expr, $g:expr, $o:expr) => {
+            Mode::new($u, $g, $o)
+        };
+        ($ur:expr, $uw:expr, $ux:expr, $gr:expr, $gw:expr, $gx:expr, $or:expr, $ow:expr, $ox: expr) => {
+            Mode::new(
+                perm!($ur, $uw, $ux),
+                perm!($gr, $gw, $gx),
+                perm!($or, $ow, $ox),
+            )
+        };
+    }
+
+    // TODO: Add failing tests
+    #[test]
+    fn test_perm_num() -> Result<(), Box<dyn std::error::Error>> {
+        use perm::*;
+
+        macro_rules! test_perm_num {
+            ($c:expr, $p:expr) => {{
+                assert_eq!($c, $p.as_num());
+                assert_eq!($p, Perm::from_num($c)?);
+                assert_eq!(Some($p), Perm::from_octal_digit($c.chars().next().unwrap()))
+            }};
+            ($c:expr, $r:expr, $w:expr, $x:expr) => {
+                test_perm_num!($c, perm!($r, $w, $x))
+            };
+        }
+
+        test_perm_num!("7", RWX);
+        test_perm_num!("6", RW);
+        test_perm_num!("5", RX);
+        test_perm_num!("4", R);
+        test_perm_num!("3", WX);
+        test_perm_num!("2", W);
+        test_perm_num!("1", X);
+        test_perm_num!("0", EMPTY);
+
+        for (i, p) in ALL.iter().enumerate() {
+            assert_eq!(*p, Perm::from_num(&i.to_string())?);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_perm_bitops() {
+        use perm::*;
+
+        let (r, x) = (&R, &X);
+        assert_eq!(RX, R | X);
+        assert_eq!(RX, r | x);
+        assert_eq!(RX, R | x);
+        assert_eq!(RX, r | X);
+        assert_eq!(W, RW & WX);
+        assert_eq!(RX, RW ^ WX);
+        assert_eq!(EMPTY, !RWX);
+        assert_eq!(W, !&RX);
+
+        for a in ALL.iter() {
+            assert_eq!(*a, !!a);
+            for b in ALL.iter() {
+                let (or, and, xor) = (a | b, a & b, a ^ b);
+                assert_eq!(a.read || b.read, or.read);
+                assert_eq!(a.write && b.write, and.write);
+                assert_eq!(a.execute != b.execute, xor.execute);
+                assert_eq!(or, xor | and);
+            }
+        }
+    }
+
+    #[test]
+    fn test_perm_sym() -> Result<(), Box<dyn std::error::Error>> {
+        use perm::*;
+
+        macro_rules! test_perm_sym {
+            ($s:expr, $fs:expr, $p:expr) => {
+                assert_eq!($s, $p.as_sym());
+                assert_eq!($fs, $p.as_sym_full());
+                assert_eq!($fs, $p.to_string());
+                assert_eq!($p, Perm::from_sym_full($fs)?)
+            };
+            ($s:expr, $fs:expr, $r:expr, $w:expr, $x:expr) => {
+                test_perm_sym!($s, $fs, perm!($r, $w, $x))
+            };
+        }
+
+        macro_rules! test_perm_sym_e {
+            ($fs:expr, $err:expr) => {
+                assert_eq!($err, Perm::from_sym_full($fs).unwrap_err())
+            };
+        }
+
+        test_perm_sym!("rwx", "rwx", RWX);
+        test_perm_sym!("rw", "rw-", RW);
+        test_perm_sym!("rx", "r-x", RX);
+        test_perm_sym!("r", "r--", R);
+        test_perm_sym!("wx", "-wx", WX);
+        test_perm_sym!("w", "-w-", W);
+        test_perm_sym!("x", "--x", X);
+        test_perm_sym!("", "---", EMPTY);
+
+        test_perm_sym_e!("", ParseError::UnexpectedEoi { pos: 0 });
+        test_perm_sym_e!("r", ParseError::UnexpectedEoi { pos: 1 });
+        test_perm_sym_e!("rw", ParseError::UnexpectedEoi { pos: 2 });
+        test_perm_sym_e!(
+            "x",
+            ParseError::UnexpectedChar {
+                pos: 0,
+                c: 'x',
+                expected: Some(vec!['r', '-'])
+            }
+        );
+        test_perm_sym_e!(
+            "rr",
+            ParseError::UnexpectedChar {
+                pos: 1,
+                c: 'r',
+                expected: Some(vec!['w', '-'])
+            }
+        );
+        test_perm_sym_e!(
+            "rwxr",
+            ParseError::UnexpectedChar {
+                pos: 3,
+                c: 'r',
+                expected: None
+            }
+        );
+
+        for p in &ALL {
+            let (buf, len) = p.as_sym_bytes();
+            assert_eq!(p.as_sym().as_bytes(), &buf[..len]);
+        }
+
+        Ok(())
+    }
+
+    // TODO: Add failing tests
+    #[test]
+    fn test_mode_num() -> Result<(), Box<dyn std::error::Error>> {
+        macro_rules! test_mode_num {
+            ($c:expr; $ur:expr, $uw:expr, $ux:expr; $gr:expr, $gw:expr, $gx:expr; $or:expr, $ow:expr, $ox: expr) => {
+                assert_eq!(
+                    $c,
+                    mode!($ur, $uw, $ux, $gr, $gw, $gx, $or, $ow, $ox).as_num()
+                );
+                assert_eq!(
+                    mode!($ur, $uw, $ux, $gr, $gw, $gx, $or, $ow, $ox),
+                    Mode::from_num($c)?
+                )
+            };
+        }
+
+        test_mode_num!("777"; true, true, true; true, true, true; true, true, true);
+        test_mode_num!("755"; true, true, true; true, false, true; true, false, true);
+        test_mode_num!("666"; true, true, false; true, true, false; true, true, false);
+        test_mode_num!("644"; true, true, false; true, false, false; true, false, false);
+        test_mode_num!("400"; true, false, false; false, false, false; false, false, false);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mode_num_e() {
+        // The implementation of Mode::from_num before it parsed bytes directly, which reported an
+        // invalid digit one position past it.
+        fn from_num_chars(num: &str) -> Result<Mode, ParseError> {
+            #[inline]
+            fn next_val(pos: &mut usize, chars: &mut Chars) -> Result<Perm, ParseError> {
+                let c = chars
+                    .next()
+                    .ok_or(ParseError::UnexpectedEoi { pos: *pos })?;
+                *pos += 1;
+                Perm::from_num(&c.to_string()).map_err(|err| match err {
+                    ParseError::UnexpectedChar {
+                        c,
+                        pos: p,
+                        expected,
+                    } => ParseError::UnexpectedChar {
+                        c,
+                        pos: p + *pos,
+                        expected,
+                    },
+                    ParseError::UnexpectedEoi { pos: p } => {
+                        ParseError::UnexpectedEoi { pos: p + *pos }
+                    }
+                    err => err,
+                })
+            }
+
+            let mut chars = num.chars();
+            let mut pos = 0;
+            let user = next_val(&mut pos, &mut chars)?;
+            let group = next_val(&mut pos, &mut chars)?;
+            let other = next_val(&mut pos, &mut chars)?;
+
+            if let Some(c) = chars.next() {
+                Err(ParseError::UnexpectedChar {
+                    pos,
+                    c,
+                    expected: None,
+                })
+            } else {
+                Ok(Mode::new(user, group, other))
+            }
+        }
+
+        // Move an invalid digit reported by from_num_chars back onto the digit.
+        fn fix_pos(err: ParseError) -> ParseError {
+            match err {
+                ParseError::UnexpectedChar {
+                    pos,
+                    c,
+                    expected: expected @ Some(_),
+                } => ParseError::UnexpectedChar {
+                    pos: pos - 1,
+                    c,
+                    expected,
+                },
+                err => err,
+            }
+        }
+
+        macro_rules! test_mode_num_e {
+            ($c:expr, $err:expr) => {
+                assert_eq!($err, Mode::from_num($c).unwrap_err());
+                assert_eq!(from_num_chars($c).map_err(fix_pos), Mode::from_num($c));
+            };
+        }
+
+        // Inputs of more than three characters may start with a special bits digit, which the
+        // previous implementation did not accept, so they are not compared with it.
+        macro_rules! test_mode_num_special_e {
+            ($c:expr, $err:expr) => {
+                assert_eq!($err, Mode::from_num($c).unwrap_err());
+            };
+        }
+
+        test_mode_num_e!("", ParseError::UnexpectedEoi { pos: 0 });
+        test_mode_num_e!("7", ParseError::UnexpectedEoi { pos: 1 });
+        test_mode_num_e!("75", ParseError::UnexpectedEoi { pos: 2 });
+        test_mode_num_e!(
+            "855",
+            ParseError::UnexpectedChar {
+                pos: 0,
+                c: '8',
+                expected: Some(vec!['0', '1', '2', '3', '4', '5', '6', '7'])
+            }
+        );
+        test_mode_num_e!(
+            "7a5",
+            ParseError::UnexpectedChar {
+                pos: 1,
+                c: 'a',
+                expected: Some(vec!['0', '1', '2', '3', '4', '5', '6', '7'])
+            }
+        );
+        test_mode_num_e!(
+            "75é",
+            ParseError::UnexpectedChar {
+                pos: 2,
+                c: 'é',
+                expected: Some(vec!['0', '1', '2', '3', '4', '5', '6', '7'])
+            }
+        );
+        test_mode_num_special_e!(
+            "6008",
+            ParseError::UnexpectedChar {
+                pos: 3,
+                c: '8',
+                expected: Some(vec!['0', '1', '2', '3', '4', '5', '6', '7'])
+            }
+        );
+        test_mode_num_special_e!(
+            "8755",
+            ParseError::UnexpectedChar {
+                pos: 0,
+                c: '8',
+                expected: Some(vec!['0', '1', '2', '3', '4', '5', '6', '7'])
+            }
+        );
+        test_mode_num_special_e!(
+            "75555",
+            ParseError::UnexpectedChar {
+                pos: 4,
+                c: '5',
+                expected: None
+            }
+        );
+        test_mode_num_special_e!(
+            "755ü",
+            ParseError::UnexpectedChar {
+                pos: 3,
+                c: 'ü',
+                expected: Some(vec!['0', '1', '2', '3', '4', '5', '6', '7'])
+            }
+        );
+        test_mode_num_special_e!(
+            "4755ü",
+            ParseError::UnexpectedChar {
+                pos: 4,
+                c: 'ü',
+                expected: None
+            }
+        );
+
+        // Inputs of up to three characters are parsed as before special bits were supported.
+        let digits = ["", "0", "7", "8", "a", "é"];
+        for u in &digits {
+            for g in &digits {
+                for o in &digits {
+                    let num = [*u, *g, *o].concat();
+                    assert_eq!(
+                        from_num_chars(&num).map_err(fix_pos),
+                        Mode::from_num(&num),
+                        "{:?}",
+                        num
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_mode_num_ascii() -> Result<(), Box<dyn std::error::Error>> {
+        for num in &["000", "644", "755", "777", "421", "135"] {
+            let mode = Mode::from_num(num)?;
+            let buf = mode.write_num_ascii();
+            assert_eq!(num.as_bytes(), &buf);
+            assert_eq!(mode, Mode::from_num_ascii(&buf)?);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mode_num_bytes_const() -> Result<(), Box<dyn std::error::Error>> {
+        const M: Mode = Mode::from_num_bytes_const(b"640");
+        const S: Mode = Mode::from_num_bytes_const(b"4755");
+        assert_eq!(Mode::from_num("640")?, M);
+        assert_eq!(Mode::from_num("4755")?, S);
+
+        for n in 0..0o10000 {
+            let num = format!("{:03o}", n);
+            assert_eq!(
+                Mode::from_num(&num)?,
+                Mode::from_num_bytes_const(num.as_bytes())
+            );
+        }
+
+        for num in ["", "75", "758", "8755", "07777"] {
+            let result = std::panic::catch_unwind(|| Mode::from_num_bytes_const(num.as_bytes()));
+            assert!(result.is_err());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decimal_octal() {
+        for bits in 0..0o10000 {
+            let m = Mode::from_bits(bits);
+            let n = m.as_decimal_octal();
+            assert_eq!(m.as_num().parse::<u32>().unwrap(), n);
+            assert_eq!(Some(&m), Mode::from_decimal_octal(n).as_ref());
+        }
+
+        for n in [8, 80, 800, 778, 999, 8000, 10000, u32::MAX] {
+            assert_eq!(None, Mode::from_decimal_octal(n));
+        }
+    }
+
+    #[test]
+    fn test_mode_stable_id() -> Result<(), Box<dyn std::error::Error>> {
+        assert_eq!(493, Mode::from_num("755")?.stable_id());
+        assert_eq!(0, Mode::from_num("000")?.stable_id());
+        assert_eq!(4095, Mode::from_num("7777")?.stable_id());
+
+        for bits in 0..0o10000 {
+            assert_eq!(bits, Mode::from_bits(bits).stable_id());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mode_u32() {
+        for bits in 0..0o10000 {
+            assert_eq!(bits, u32::from(Mode::from(bits)));
+            assert_eq!(Mode::from(bits), Mode::from(bits | 0o170000));
+        }
+        assert_eq!(0o644, u32::from(Mode::from(u32::MAX ^ 0o7133)));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_mode_os_str() -> Result<(), Box<dyn std::error::Error>> {
+        use std::ffi::OsString;
+
+        assert_eq!(
+            Mode::from_num("755")?,
+            Mode::from_os_str(&OsString::from("rwxr-xr-x"))?
+        );
+        assert_eq!(
+            ParseError::ShortNum {
+                len: 2,
+                perm: false
+            },
+            Mode::from_os_str(&OsString::from("75")).unwrap_err()
+        );
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStrExt;
+
+            assert_eq!(
+                ParseError::NonUtf8,
+                Mode::from_os_str(OsStr::from_bytes(b"75\xff")).unwrap_err()
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mode_u32_array() -> Result<(), Box<dyn std::error::Error>> {
+        for bits in 0..0o1000 {
+            let m = Mode::from_bits(bits);
+            assert_eq!(m, Mode::from_u32_array(&m.to_u32_array())?);
+        }
+        assert_eq!([7, 5, 5], Mode::from_num("4755")?.to_u32_array());
+
+        assert_eq!(
+            ParseError::OutOfRange { index: 0, value: 8 },
+            Mode::from_u32_array(&[8, 0, 0]).unwrap_err()
+        );
+        assert_eq!(
+            ParseError::OutOfRange {
+                index: 2,
+                value: u32::MAX
+            },
+            Mode::from_u32_array(&[0, 7, u32::MAX]).unwrap_err()
+        );
+        assert_eq!(
+            "value 8 at index 0 is out of range 0-7",
+            ParseError::OutOfRange { index: 0, value: 8 }.to_string()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mode_parse_many() -> Result<(), Box<dyn std::error::Error>> {
+        let input = "755\t644\n\
+                     rwxr-x---  7\n\
+                     \n\
+                     rwxr-xr-z 07777 000\n";
+
+        assert_eq!(
+            vec![
+                Ok(Mode::from_num("755")?),
+                Ok(Mode::from_num("644")?),
+                Ok(Mode::from_num("750")?),
+                Err(ParseError::ShortNum { len: 1, perm: true }),
+                Err(ParseError::UnexpectedChar {
+                    pos: 8,
+                    c: 'z',
+                    expected: Some(vec!['x', 't', 'T', '-'])
+                }),
+                Err(ParseError::UnexpectedChar {
+                    pos: 4,
+                    c: '7',
+                    expected: None
+                }),
+                Ok(Mode::from_num("000")?),
+            ],
+            Mode::parse_many(input)
+        );
+        assert!(Mode::parse_many(" \n\t").is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_perm_set() {
+        use perm::*;
+
+        for perm in ALL.iter() {
+            for (bit, mask) in [
+                (PermBit::Read, R),
+                (PermBit::Write, W),
+                (PermBit::Execute, X),
+            ] {
+                let mut p = *perm;
+                p.set(bit, true);
+                assert_eq!(*perm | mask, p);
+                p.set(bit, false);
+                assert_eq!(*perm & !mask, p);
+
+                let mut p = *perm;
+                p.toggle(bit);
+                assert_eq!(*perm ^ mask, p);
+            }
+        }
+
+        let mut p = EMPTY;
+        p.set_read(true);
+        p.set_execute(true);
+        assert_eq!(RX, p);
+        p.set_write(true);
+        p.set_read(false);
+        assert_eq!(WX, p);
+        p.toggle_read();
+        p.toggle_write();
+        p.toggle_execute();
+        assert_eq!(R, p);
+    }
+
+    #[test]
+    fn test_default() -> Result<(), Box<dyn std::error::Error>> {
+        assert_eq!(perm::EMPTY, Perm::default());
+        assert_eq!(Mode::from_num("000")?, Mode::default());
+
+        let m = Mode {
+            user: perm::RW,
+            ..Default::default()
+        };
+        assert_eq!("600", m.as_num());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ord() {
+        // The derived orderings depend on the order of the fields, which must match the octal
+        // values.
+        for a in 0..0o10 {
+            for b in 0..0o10 {
+                assert_eq!(
+                    a.cmp(&b),
+                    Perm::from_bits(a).cmp(&Perm::from_bits(b)),
+                    "{:o} {:o}",
+                    a,
+                    b
+                );
+            }
+        }
+
+        let key = |bits: u16| (bits & 0o777, bits >> 9);
+        for a in (0..0o10000).step_by(5) {
+            for b in (0..0o10000).step_by(7) {
+                assert_eq!(
+                    key(a).cmp(&key(b)),
+                    Mode::from_bits(a).cmp(&Mode::from_bits(b)),
+                    "{:o} {:o}",
+                    a,
+                    b
+                );
+            }
+        }
     }
 
-    // TODO: Add failing tests
     #[test]
-    fn test_perm_num() -> Result<(), Box<dyn std::error::Error>> {
-        use perm::*;
+    fn test_contains() {
+        for a in 0..0o10 {
+            for b in 0..0o10 {
+                let (pa, pb) = (Perm::from_bits(a), Perm::from_bits(b));
+                assert_eq!(pa | pb == pa, pa.contains(&pb));
+            }
+        }
 
-        macro_rules! test_perm_num {
-            ($c:expr, $p:expr) => {{
-                assert_eq!($c, $p.as_num());
-                assert_eq!($p, Perm::from_num($c)?)
-            }};
-            ($c:expr, $r:expr, $w:expr, $x:expr) => {
-                test_perm_num!($c, perm!($r, $w, $x))
+        for a in (0..0o10000).step_by(3) {
+            for b in (0..0o10000).step_by(11) {
+                let (ma, mb) = (Mode::from_bits(a), Mode::from_bits(b));
+                assert_eq!(
+                    ma.user.contains(&mb.user)
+                        && ma.group.contains(&mb.group)
+                        && ma.other.contains(&mb.other)
+                        && (ma.setuid || !mb.setuid)
+                        && (ma.setgid || !mb.setgid)
+                        && (ma.sticky || !mb.sticky),
+                    ma.contains(&mb)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_mode_matches_pattern() -> Result<(), Box<dyn std::error::Error>> {
+        let mode = Mode::from_num("744")?;
+        assert!(mode.matches_pattern("rwxr--r--")?);
+        assert!(mode.matches_pattern("rw?r--r--")?);
+        assert!(mode.matches_pattern("?????????")?);
+        assert!(Mode::from_num("644")?.matches_pattern("rw?r--r--")?);
+        assert!(!Mode::from_num("664")?.matches_pattern("rw?r--r--")?);
+        assert!(!mode.matches_pattern("rwxr--r-x")?);
+        assert!(!mode.matches_pattern("rwsr--r--")?);
+        assert!(Mode::from_num("1777")?.matches_pattern("rwxrwx??t")?);
+        assert!(!Mode::from_num("777")?.matches_pattern("??????rwt")?);
+
+        for bits in 0..0o10000 {
+            let mode = Mode::from_bits(bits);
+            assert!(mode.matches_pattern(mode.as_sym_static())?);
+            assert!(mode.matches_pattern("?????????")?);
+        }
+
+        assert_eq!(
+            Err(ParseError::UnexpectedChar {
+                pos: 2,
+                c: 't',
+                expected: Some(vec!['x', 's', 'S', '-', '?'])
+            }),
+            mode.matches_pattern("rwtr--r--")
+        );
+        assert_eq!(
+            Err(ParseError::UnexpectedEoi { pos: 6 }),
+            mode.matches_pattern("rw?r--")
+        );
+        assert_eq!(
+            Err(ParseError::UnexpectedChar {
+                pos: 9,
+                c: '?',
+                expected: None
+            }),
+            mode.matches_pattern("rw?r--r--?")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mode_permissiveness() -> Result<(), Box<dyn std::error::Error>> {
+        macro_rules! test {
+            ($score:expr, $ratio:expr, $num:expr) => {
+                let mode = Mode::from_num($num)?;
+                assert_eq!($score, mode.permissiveness_score());
+                assert_eq!($ratio, mode.permissiveness_ratio());
             };
         }
 
-        test_perm_num!("7", RWX);
-        test_perm_num!("6", RW);
-        test_perm_num!("5", RX);
-        test_perm_num!("4", R);
-        test_perm_num!("3", WX);
-        test_perm_num!("2", W);
-        test_perm_num!("1", X);
-        test_perm_num!("0", EMPTY);
+        test!(0, 0.0, "000");
+        test!(9, 1.0, "777");
+        test!(6, 6.0 / 9.0, "751");
+        test!(8, 8.0 / 9.0, "776");
+        test!(3, 3.0 / 9.0, "4700");
+
+        for bits in 0..0o10000 {
+            let mode = Mode::from_bits(bits);
+            assert_eq!(mode.total_bits(), u32::from(mode.permissiveness_score()));
+        }
 
         Ok(())
     }
 
     #[test]
-    fn test_perm_sym() -> Result<(), Box<dyn std::error::Error>> {
-        use perm::*;
+    fn test_mode_acl() -> Result<(), Box<dyn std::error::Error>> {
+        let mode = Mode::from_num("755")?;
+        assert_eq!(
+            (mode, Some(AclIndicator::Acl)),
+            Mode::from_sym_with_acl("rwxr-xr-x+")?
+        );
+        assert_eq!(
+            (mode, Some(AclIndicator::SecurityContext)),
+            Mode::from_sym_with_acl("rwxr-xr-x.")?
+        );
+        assert_eq!((mode, None), Mode::from_sym_with_acl("rwxr-xr-x")?);
 
-        macro_rules! test_perm_sym {
-            ($s:expr, $fs:expr, $p:expr) => {
-                assert_eq!($s, $p.as_sym());
-                assert_eq!($fs, $p.as_sym_full());
-                assert_eq!($p, Perm::from_sym_full($fs)?)
+        assert_eq!(
+            (mode, Some(AclIndicator::Acl)),
+            Mode::from_ls("-rwxr-xr-x+")?
+        );
+        assert_eq!(
+            (mode, Some(AclIndicator::SecurityContext)),
+            Mode::from_ls("drwxr-xr-x.")?
+        );
+        assert_eq!((mode, None), Mode::from_ls("lrwxr-xr-x")?);
+
+        for bits in 0..0o10000 {
+            let mode = Mode::from_bits(bits);
+            let sym = mode.as_sym_static();
+            assert_eq!((mode, None), Mode::from_sym_with_acl(sym)?);
+            assert_eq!(
+                (mode, Some(AclIndicator::Acl)),
+                Mode::from_ls(&format!("-{}+", sym))?
+            );
+        }
+
+        assert_eq!(
+            Err(ParseError::UnexpectedChar {
+                pos: 10,
+                c: '+',
+                expected: None
+            }),
+            Mode::from_sym_with_acl("rwxr-xr-x.+")
+        );
+        assert_eq!(
+            Err(ParseError::UnexpectedChar {
+                pos: 6,
+                c: '+',
+                expected: Some(vec!['r', '-'])
+            }),
+            Mode::from_sym_with_acl("rwxr-x+")
+        );
+        assert_eq!(
+            Err(ParseError::UnexpectedChar {
+                pos: 0,
+                c: 'r',
+                expected: Some(vec!['-', 'b', 'c', 'd', 'l', 'p', 's'])
+            }),
+            Mode::from_ls("rwxr-xr-x+")
+        );
+        assert_eq!(
+            Err(ParseError::UnexpectedChar {
+                pos: 3,
+                c: 'z',
+                expected: Some(vec!['x', 's', 'S', '-'])
+            }),
+            Mode::from_ls("-rwzr-xr-x")
+        );
+        assert_eq!(Err(ParseError::UnexpectedEoi { pos: 0 }), Mode::from_ls(""));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mode_install_arg() -> Result<(), Box<dyn std::error::Error>> {
+        assert_eq!("755", Mode::from_num("755")?.to_install_arg());
+        assert_eq!("000", Mode::from_num("000")?.to_install_arg());
+        assert_eq!("044", Mode::from_sym("---r--r--")?.to_install_arg());
+        assert_eq!("4755", Mode::from_num("4755")?.to_install_arg());
+        assert_eq!("1000", Mode::from_num("1000")?.to_install_arg());
+
+        for bits in 0..0o10000 {
+            let mode = Mode::from_bits(bits);
+            let arg = mode.to_install_arg();
+            assert_eq!(if bits > 0o777 { 4 } else { 3 }, arg.len());
+            assert_eq!(u32::from(bits), u32::from_str_radix(&arg, 8)?);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mode_all() {
+        let modes: Vec<_> = Mode::all().collect();
+        assert_eq!(0o10000, modes.len());
+        for (bits, mode) in modes.iter().enumerate() {
+            assert_eq!(bits as u16, mode.stable_id());
+        }
+
+        let sorted = Mode::sorted_unique(&modes);
+        assert_eq!(modes.len(), sorted.len());
+        assert!(sorted.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn test_mode_access_for() -> Result<(), Box<dyn std::error::Error>> {
+        let mode = Mode::from_num("640")?;
+        assert_eq!(perm::RW, mode.access_for(true, false));
+        assert_eq!(perm::RW, mode.access_for(true, true));
+        assert_eq!(perm::R, mode.access_for(false, true));
+        assert_eq!(perm::EMPTY, mode.access_for(false, false));
+
+        let mode = Mode::from_num("074")?;
+        assert_eq!(perm::EMPTY, mode.access_for(true, true));
+        assert_eq!(perm::RWX, mode.access_for(false, true));
+        assert_eq!(perm::R, mode.access_for(false, false));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mode_find_first() -> Result<(), Box<dyn std::error::Error>> {
+        macro_rules! test {
+            ($c:expr, $text:expr) => {
+                assert_eq!($c, Mode::find_first($text))
             };
-            ($s:expr, $fs:expr, $r:expr, $w:expr, $x:expr) => {
-                test_perm_sym!($s, $fs, perm!($r, $w, $x))
+        }
+
+        test!(Some((Mode::from_num("755")?, 8)), "perm is 755 now");
+        test!(Some((Mode::from_num("755")?, 0)), "755");
+        test!(Some((Mode::from_num("4755")?, 5)), "mode=4755");
+        test!(Some((Mode::from_num("644")?, 12)), "changed to (0644)");
+        test!(Some((Mode::from_num("644")?, 1)), "-rw-r--r-- 1 user");
+        test!(Some((Mode::from_num("640")?, 11)), "mode of é rw-r-----");
+        test!(Some((Mode::from_num("600")?, 13)), "build 12345, 600");
+        test!(None, "build 12345 passed");
+        test!(None, "perm is 75 or 8");
+        test!(None, "");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mode_raw() -> Result<(), Box<dyn std::error::Error>> {
+        macro_rules! test {
+            ($num:expr, $file_type:expr, $sym:expr, $raw:expr) => {
+                let (mode, file_type) = Mode::from_raw($raw);
+                assert_eq!(Mode::from_num($num)?, mode);
+                assert_eq!(Some($file_type), file_type);
+                assert_eq!($sym, mode.as_sym_with_type($file_type));
+                assert_eq!((mode, None), Mode::from_ls($sym)?);
             };
         }
 
-        macro_rules! test_perm_sym_e {
-            ($fs:expr, $err:expr) => {
-                assert_eq!($err, Perm::from_sym_full($fs).unwrap_err())
+        test!("755", FileType::Directory, "drwxr-xr-x", 0o40755);
+        test!("644", FileType::Regular, "-rw-r--r--", 0o100644);
+        test!("777", FileType::Symlink, "lrwxrwxrwx", 0o120777);
+        test!("620", FileType::CharDevice, "crw--w----", 0o20620);
+        test!("660", FileType::BlockDevice, "brw-rw----", 0o60660);
+        test!("644", FileType::Fifo, "prw-r--r--", 0o10644);
+        test!("1777", FileType::Socket, "srwxrwxrwt", 0o141777);
+
+        assert_eq!((Mode::from_num("755")?, None), Mode::from_raw(0o755));
+        assert_eq!(None, Mode::from_raw(0o170755).1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mode_icons() -> Result<(), Box<dyn std::error::Error>> {
+        let mode = Mode::from_num("755")?;
+        assert_eq!("RWX R·X R·X", mode.as_icons(IconStyle::Dots));
+        assert_eq!("RWX RwX RwX", mode.as_icons(IconStyle::Case));
+        assert_eq!("🔓🔓🔓 🔓🔒🔓 🔓🔒🔓", mode.as_icons(IconStyle::Locks));
+
+        assert_eq!(
+            "··· ··· ···",
+            Mode::from_num("000")?.as_icons(IconStyle::Dots)
+        );
+        assert_eq!(
+            "rwx rwx rwx",
+            Mode::from_num("000")?.as_icons(IconStyle::Case)
+        );
+        assert_eq!(
+            mode.as_icons(IconStyle::Case),
+            Mode::from_num("4755")?.as_icons(IconStyle::Case)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_mode_map() -> Result<(), Box<dyn std::error::Error>> {
+        for mode in (0..0o1000).map(Mode::from_bits) {
+            assert_eq!(mode, Mode::from_map(&mode.to_map()));
+        }
+        assert_eq!(
+            Mode::from_num("755")?,
+            Mode::from_map(&Mode::from_num("4755")?.to_map())
+        );
+
+        let mut map = HashMap::new();
+        assert_eq!(Mode::from_num("000")?, Mode::from_map(&map));
+        map.insert(Class::Group, perm::RX);
+        assert_eq!(Mode::from_num("050")?, Mode::from_map(&map));
+        map.insert(Class::User, perm::RWX);
+        assert_eq!(Mode::from_num("750")?, Mode::from_map(&map));
+        map.insert(Class::Other, perm::R);
+        assert_eq!(Mode::from_num("754")?, Mode::from_map(&map));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mode_sym_strict() -> Result<(), Box<dyn std::error::Error>> {
+        assert_eq!(Mode::from_num("755")?, Mode::from_sym_strict("rwxr-xr-x")?);
+        assert_eq!(Mode::from_num("4755")?, Mode::from_sym_strict("rwsr-xr-x")?);
+
+        for mode in Mode::all() {
+            assert_eq!(Ok(mode), Mode::from_sym_strict(mode.as_sym_static()));
+        }
+
+        macro_rules! test_len {
+            ($len:expr, $sym:expr) => {
+                let err = Mode::from_sym_strict($sym).unwrap_err();
+                assert_eq!(
+                    ParseError::InvalidLength {
+                        len: $len,
+                        expected: 9
+                    },
+                    err
+                );
+                assert_eq!($len.min(9), err.pos());
             };
         }
 
-        test_perm_sym!("rwx", "rwx", RWX);
-        test_perm_sym!("rw", "rw-", RW);
-        test_perm_sym!("rx", "r-x", RX);
-        test_perm_sym!("r", "r--", R);
-        test_perm_sym!("wx", "-wx", WX);
-        test_perm_sym!("w", "-w-", W);
-        test_perm_sym!("x", "--x", X);
-        test_perm_sym!("", "---", EMPTY);
+        test_len!(3, "rwx");
+        test_len!(10, "rwxr-xr-xr");
+        test_len!(0, "");
+        test_len!(12, "rwxrwxrwxrwx");
 
-        test_perm_sym_e!("", ParseError::UnexpectedEoi { pos: 0 });
-        test_perm_sym_e!("r", ParseError::UnexpectedEoi { pos: 1 });
-        test_perm_sym_e!("rw", ParseError::UnexpectedEoi { pos: 2 });
-        test_perm_sym_e!(
-            "x",
+        assert_eq!(
+            Err(ParseError::UnexpectedChar {
+                pos: 5,
+                c: 'z',
+                expected: Some(vec!['x', 's', 'S', '-'])
+            }),
+            Mode::from_sym_strict("rwxr-zr-x")
+        );
+        assert_eq!(
+            "rwx\n   ^ expected 9 characters, found 3",
+            Mode::from_sym_strict("rwx")
+                .unwrap_err()
+                .render_with_input("rwx")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mode_sorted_unique() -> Result<(), Box<dyn std::error::Error>> {
+        let modes = ["777", "4755", "000", "777", "755", "000", "1000", "755"]
+            .iter()
+            .map(|num| Mode::from_num(num))
+            .collect::<Result<Vec<_>, _>>()?;
+        let sorted: Vec<_> = Mode::sorted_unique(&modes)
+            .iter()
+            .map(Mode::as_num)
+            .collect();
+        assert_eq!(vec!["000", "1000", "755", "4755", "777"], sorted);
+
+        let mut expected = modes.clone();
+        expected.sort();
+        expected.dedup();
+        assert_eq!(expected, Mode::sorted_unique(&modes));
+
+        assert!(Mode::sorted_unique(&[]).is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mode_bool_csv() -> Result<(), Box<dyn std::error::Error>> {
+        assert_eq!(
+            "true,true,true,true,false,true,true,false,true",
+            Mode::from_num("755")?.to_bool_csv()
+        );
+        assert_eq!(
+            "true,true,true,true,false,true,true,false,true",
+            Mode::from_num("4755")?.to_bool_csv()
+        );
+
+        for bits in 0..0o1000 {
+            let m = Mode::from_bits(bits);
+            assert_eq!(m, Mode::from_bool_csv(&m.to_bool_csv())?);
+        }
+
+        macro_rules! test_mode_bool_csv_e {
+            ($csv:expr, $err:expr) => {
+                assert_eq!($err, Mode::from_bool_csv($csv).unwrap_err())
+            };
+        }
+
+        let all = "true,true,true,true,true,true,true,true,true";
+        test_mode_bool_csv_e!("", ParseError::UnexpectedEoi { pos: 0 });
+        test_mode_bool_csv_e!("tru", ParseError::UnexpectedEoi { pos: 3 });
+        test_mode_bool_csv_e!(
+            "fals3",
             ParseError::UnexpectedChar {
-                pos: 0,
-                c: 'x',
-                expected: Some(vec!['r', '-'])
+                pos: 4,
+                c: '3',
+                expected: Some(vec!['e'])
             }
         );
-        test_perm_sym_e!(
-            "rr",
+        test_mode_bool_csv_e!(
+            "true;false",
+            ParseError::UnexpectedChar {
+                pos: 4,
+                c: ';',
+                expected: Some(vec![','])
+            }
+        );
+        test_mode_bool_csv_e!(
+            &format!("{},true", all),
+            ParseError::UnexpectedChar {
+                pos: all.len(),
+                c: ',',
+                expected: None
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mode_kv() -> Result<(), Box<dyn std::error::Error>> {
+        assert_eq!(
+            Mode::from_num("751")?,
+            Mode::from_kv(&[("other", "--x"), ("user", "rwx"), ("group", "r-x")])?
+        );
+        assert_eq!(
+            Mode::from_num("604")?,
+            Mode::from_kv(&[("user", "rw-"), ("other", "r--")])?
+        );
+        assert_eq!(Mode::from_num("000")?, Mode::from_kv(&[])?);
+        assert_eq!(
+            Mode::from_num("500")?,
+            Mode::from_kv(&[("user", "rwx"), ("user", "r-x")])?
+        );
+
+        assert_eq!(
+            ParseError::InvalidKey {
+                key: "User".to_string()
+            },
+            Mode::from_kv(&[("User", "rwx")]).unwrap_err()
+        );
+        assert_eq!(
             ParseError::UnexpectedChar {
                 pos: 1,
-                c: 'r',
+                c: 'x',
                 expected: Some(vec!['w', '-'])
+            },
+            Mode::from_kv(&[("user", "rwx"), ("group", "rx")]).unwrap_err()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mode_mask_parse() -> Result<(), Box<dyn std::error::Error>> {
+        use perm::*;
+
+        macro_rules! test_mode_mask_parse {
+            ($s:expr, $u:expr, $g:expr, $o:expr) => {
+                let mask = ModeMask::parse($s)?;
+                assert_eq!(($u, $g, $o), (mask.user, mask.group, mask.other))
+            };
+        }
+
+        macro_rules! test_mode_mask_parse_e {
+            ($s:expr, $err:expr) => {
+                assert_eq!($err, ModeMask::parse($s).unwrap_err())
+            };
+        }
+
+        test_mode_mask_parse!("u7", Some(RWX), None, None);
+        test_mode_mask_parse!("g0", None, Some(EMPTY), None);
+        test_mode_mask_parse!("o5", None, None, Some(RX));
+        test_mode_mask_parse!("750", Some(RWX), Some(RX), Some(EMPTY));
+        test_mode_mask_parse!("rw-r--r--", Some(RW), Some(R), Some(R));
+
+        let mask = ModeMask::parse("u7")?;
+        assert_eq!(Mode::from_num("700")?, mask.apply(&Mode::from_num("000")?));
+        assert_eq!(Mode::from_num("755")?, mask.apply(&Mode::from_num("055")?));
+        assert_eq!(Mode::from_num("755")?, mask.apply(&Mode::from_num("755")?));
+        assert_eq!(
+            Mode::from_num("4744")?,
+            mask.apply(&Mode::from_num("4644")?)
+        );
+        assert_eq!((None, None, None), (mask.setuid, mask.setgid, mask.sticky));
+
+        for s in &["4755", "rwsr-xr-x"] {
+            let mask = ModeMask::parse(s)?;
+            assert_eq!(
+                (Some(true), Some(false), Some(false)),
+                (mask.setuid, mask.setgid, mask.sticky)
+            );
+            assert_eq!(Mode::from_num("4755")?, mask.apply(&Mode::from_num("644")?));
+        }
+        assert_eq!(
+            Mode::from_num("755")?,
+            ModeMask::parse("755")?.apply(&Mode::from_num("3644")?)
+        );
+
+        test_mode_mask_parse_e!("u", ParseError::UnexpectedEoi { pos: 1 });
+        test_mode_mask_parse_e!(
+            "u8",
+            ParseError::UnexpectedChar {
+                pos: 1,
+                c: '8',
+                expected: Some(octal_digits())
             }
         );
-        test_perm_sym_e!(
-            "rwxr",
+        test_mode_mask_parse_e!(
+            "gr",
             ParseError::UnexpectedChar {
-                pos: 3,
+                pos: 1,
                 c: 'r',
+                expected: Some(octal_digits())
+            }
+        );
+        test_mode_mask_parse_e!(
+            "o755",
+            ParseError::UnexpectedChar {
+                pos: 2,
+                c: '5',
                 expected: None
             }
         );
+        test_mode_mask_parse_e!(
+            "a7",
+            ParseError::UnexpectedChar {
+                pos: 0,
+                c: 'a',
+                expected: Some(vec!['r', '-'])
+            }
+        );
+        test_mode_mask_parse_e!("7", ParseError::ShortNum { len: 1, perm: true });
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mode_explain_octal() -> Result<(), Box<dyn std::error::Error>> {
+        assert_eq!(
+            "7 = 4(r)+2(w)+1(x), 5 = 4(r)+1(x), 5 = 4(r)+1(x)",
+            Mode::from_num("755")?.explain_octal()
+        );
+        assert_eq!(
+            "3 = 2(w)+1(x), 2 = 2(w), 1 = 1(x)",
+            Mode::from_num("321")?.explain_octal()
+        );
+        assert_eq!(
+            "0 = 0, 0 = 0, 0 = 0",
+            Mode::from_num("000")?.explain_octal()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mode_num_chars() -> Result<(), Box<dyn std::error::Error>> {
+        for num in &["000", "644", "755", "777", "421", "135"] {
+            let mut chars = num.chars();
+            let (u, g, o) = (chars.next(), chars.next(), chars.next());
+            assert_eq!(
+                Mode::from_num(num)?,
+                Mode::from_num_chars(u.unwrap(), g.unwrap(), o.unwrap())?
+            );
+        }
+
+        for (pos, (u, g, o)) in [('8', '5', '5'), ('7', 'r', '5'), ('7', '5', '-')]
+            .iter()
+            .enumerate()
+        {
+            assert_eq!(
+                ParseError::UnexpectedChar {
+                    pos,
+                    c: [*u, *g, *o][pos],
+                    expected: Some(octal_digits())
+                },
+                Mode::from_num_chars(*u, *g, *o).unwrap_err()
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lens() -> Result<(), Box<dyn std::error::Error>> {
+        for n in 0..=7 {
+            let perm = Perm::from_num(&n.to_string())?;
+            assert_eq!(Perm::NUM_LEN, perm.as_num().len());
+            assert_eq!(Perm::SYM_FULL_LEN, perm.as_sym_full().len());
+        }
+
+        for n in 0..0o1000 {
+            let mode = Mode::from_num(&format!("{:03o}", n))?;
+            assert_eq!(Mode::NUM_LEN, mode.as_num().len());
+            assert_eq!(Mode::SYM_LEN, mode.as_sym().len());
+        }
 
         Ok(())
     }
 
-    // TODO: Add failing tests
     #[test]
-    fn test_mode_num() -> Result<(), Box<dyn std::error::Error>> {
-        macro_rules! test_mode_num {
-            ($c:expr; $ur:expr, $uw:expr, $ux:expr; $gr:expr, $gw:expr, $gx:expr; $or:expr, $ow:expr, $ox: expr) => {
+    fn test_static() -> Result<(), Box<dyn std::error::Error>> {
+        for n in 0..=7 {
+            let perm = Perm::from_num(&n.to_string())?;
+            assert_eq!(perm.as_num(), perm.as_num_static());
+            assert_eq!(perm.as_sym(), perm.as_sym_static());
+            assert_eq!(perm.as_sym_full(), perm.as_sym_full_static());
+            assert_eq!(perm.as_sym_full(), perm.to_string());
+            assert_eq!(perm.as_num(), format!("{:#}", perm));
+        }
+
+        for n in 0..0o10000 {
+            let mode = Mode::from_num(&format!("{:03o}", n))?;
+            assert_eq!(mode.as_num(), mode.as_num_static());
+            assert_eq!(mode.as_sym(), mode.as_sym_static());
+            assert_eq!(mode.as_sym(), mode.to_string());
+            assert_eq!(mode.as_num(), format!("{:#}", mode));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mode_special() -> Result<(), Box<dyn std::error::Error>> {
+        macro_rules! test_mode_special {
+            ($num:expr, $sym:expr; $setuid:expr, $setgid:expr, $sticky:expr) => {
+                let mode = Mode::from_num($num)?;
                 assert_eq!(
-                    $c,
-                    mode!($ur, $uw, $ux, $gr, $gw, $gx, $or, $ow, $ox).as_num()
+                    ($setuid, $setgid, $sticky),
+                    (mode.setuid, mode.setgid, mode.sticky)
                 );
-                assert_eq!(
-                    mode!($ur, $uw, $ux, $gr, $gw, $gx, $or, $ow, $ox),
-                    Mode::from_num($c)?
-                )
+                assert_eq!($sym, mode.as_sym());
+                assert_eq!(mode, Mode::from_sym($sym)?);
+                assert_eq!($num, Mode::from_sym($sym)?.as_num());
             };
         }
 
-        test_mode_num!("777"; true, true, true; true, true, true; true, true, true);
-        test_mode_num!("755"; true, true, true; true, false, true; true, false, true);
-        test_mode_num!("666"; true, true, false; true, true, false; true, true, false);
-        test_mode_num!("644"; true, true, false; true, false, false; true, false, false);
-        test_mode_num!("400"; true, false, false; false, false, false; false, false, false);
+        test_mode_special!("4755", "rwsr-xr-x"; true, false, false);
+        test_mode_special!("4644", "rwSr--r--"; true, false, false);
+        test_mode_special!("2750", "rwxr-s---"; false, true, false);
+        test_mode_special!("2740", "rwxr-S---"; false, true, false);
+        test_mode_special!("1777", "rwxrwxrwt"; false, false, true);
+        test_mode_special!("1776", "rwxrwxrwT"; false, false, true);
+        test_mode_special!("7000", "--S--S--T"; true, true, true);
+        test_mode_special!("755", "rwxr-xr-x"; false, false, false);
+        assert_eq!("755", Mode::from_num("0755")?.as_num());
+
+        for n in 0..0o10000 {
+            let mode = Mode::from_num(&format!("{:04o}", n))?;
+            assert_eq!(mode, Mode::from_num(&mode.as_num())?);
+            assert_eq!(mode, Mode::from_sym(&mode.as_sym())?);
+        }
+
+        assert_eq!("755", Mode::from_num("4755")?.without_special().as_num());
+        for n in 0..0o10000 {
+            let mode = Mode::from_bits(n);
+            assert_eq!(Mode::from_bits(n & 0o777), mode.without_special());
+            assert!(mode.eq_ignoring_special(&mode.without_special()));
+        }
 
         Ok(())
     }
@@ -690,6 +5675,294 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_mode_lint() -> Result<(), Box<dyn std::error::Error>> {
+        use ModeWarning::*;
+
+        macro_rules! test_mode_lint {
+            ($num:expr, [$($w:expr),*]) => {
+                assert_eq!(vec![$($w),*] as Vec<ModeWarning>, Mode::from_num($num)?.lint())
+            };
+        }
+
+        test_mode_lint!("666", [WorldWritable, GroupWritableNoSticky]);
+        test_mode_lint!("757", [WorldWritable]);
+        test_mode_lint!("777", [WorldWritable, GroupWritableNoSticky]);
+        test_mode_lint!("711", [ExecutableButNotReadable]);
+        test_mode_lint!("731", [GroupWritableNoSticky, ExecutableButNotReadable]);
+        test_mode_lint!("644", []);
+        test_mode_lint!("000", []);
+        test_mode_lint!("1777", [WorldWritable]);
+        test_mode_lint!("1770", []);
+        test_mode_lint!("2770", [GroupWritableNoSticky]);
+        test_mode_lint!(
+            "4777",
+            [WorldWritable, GroupWritableNoSticky, SetuidWithWrite]
+        );
+        test_mode_lint!("4757", [WorldWritable, SetuidWithWrite]);
+        test_mode_lint!("4775", [GroupWritableNoSticky, SetuidWithWrite]);
+        test_mode_lint!("4755", []);
+        test_mode_lint!("2757", [WorldWritable]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_opt() {
+        macro_rules! test_opt {
+            ($ty:ident, $f:ident, $f_opt:ident; $($s:expr),*) => {
+                $(assert_eq!($ty::$f($s).ok(), $ty::$f_opt($s), "{:?}", $s);)*
+            };
+        }
+
+        test_opt!(Perm, from_num, from_num_opt; "", "0", "7", "8", "77", "r", "é");
+        test_opt!(
+            Perm, from_sym_full, from_sym_full_opt;
+            "", "rwx", "r-x", "---", "rw", "rwxr", "xwr", "r-é"
+        );
+        test_opt!(Mode, from_num, from_num_opt; "", "000", "755", "75", "7555", "758", "7é");
+        test_opt!(
+            Mode, from_sym, from_sym_opt;
+            "", "rwxr-xr-x", "---------", "rwxr-x", "rwxr-xr-xr", "rwxr-xr-w", "rwxr-xré"
+        );
+
+        for bits in 0..0o1000 {
+            let m = Mode::from_bits(bits);
+            assert_eq!(Some(&m), Mode::from_num_opt(&m.as_num()).as_ref());
+            assert_eq!(Some(&m), Mode::from_sym_opt(&m.as_sym()).as_ref());
+        }
+    }
+
+    #[test]
+    fn test_mode_sym_special() -> Result<(), Box<dyn std::error::Error>> {
+        macro_rules! test_mode_sym_special {
+            ($c:expr, $num:expr; $ux:expr, $gx:expr, $ox:expr) => {{
+                let m = Mode::from_num($num)?;
+                assert_eq!(
+                    ($ux, $gx, $ox),
+                    (m.user.execute, m.group.execute, m.other.execute)
+                );
+                assert_eq!($c, m.as_sym());
+                assert_eq!(m, Mode::from_sym($c)?);
+                assert_eq!(Some(&m), Mode::from_sym_opt($c).as_ref());
+
+                let separated = m.as_sym_separated(' ');
+                assert_eq!(m, Mode::from_sym_separated(&separated, ' ')?);
+            }};
+        }
+
+        macro_rules! test_mode_sym_special_e {
+            ($fs:expr, $pos:expr, $c:expr, [$($e:expr),*]) => {
+                assert_eq!(
+                    ParseError::UnexpectedChar {
+                        pos: $pos,
+                        c: $c,
+                        expected: Some(vec![$($e),*]),
+                    },
+                    Mode::from_sym($fs).unwrap_err()
+                );
+                assert_eq!(None, Mode::from_sym_opt($fs));
+            };
+        }
+
+        test_mode_sym_special!("rwsr-xr-x", "4755"; true, true, true);
+        test_mode_sym_special!("rwSr-xr-x", "4655"; false, true, true);
+        test_mode_sym_special!("rwxr-sr-x", "2755"; true, true, true);
+        test_mode_sym_special!("rwxr-Sr-x", "2745"; true, false, true);
+        test_mode_sym_special!("rwxr-xr-t", "1755"; true, true, true);
+        test_mode_sym_special!("rwxr-xr-T", "1754"; true, true, false);
+        test_mode_sym_special!("--S--S--T", "7000"; false, false, false);
+        test_mode_sym_special!("--s--s--t", "7111"; true, true, true);
+
+        test_mode_sym_special_e!("rwtr-xr-x", 2, 't', ['x', 's', 'S', '-']);
+        test_mode_sym_special_e!("rwxr-Tr-x", 5, 'T', ['x', 's', 'S', '-']);
+        test_mode_sym_special_e!("rwxr-xr-s", 8, 's', ['x', 't', 'T', '-']);
+        test_mode_sym_special_e!("rwxr-xr-S", 8, 'S', ['x', 't', 'T', '-']);
+        test_mode_sym_special_e!("sw-r--r--", 0, 's', ['r', '-']);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mode_sym_separated() -> Result<(), Box<dyn std::error::Error>> {
+        macro_rules! test_mode_sym_separated {
+            ($c:expr, $num:expr) => {{
+                let m = Mode::from_num($num)?;
+                assert_eq!($c, m.as_sym_separated('|'));
+                assert_eq!(m, Mode::from_sym_separated($c, '|')?)
+            }};
+        }
+
+        macro_rules! test_mode_sym_separated_e {
+            ($fs:expr, $err:expr) => {
+                assert_eq!($err, Mode::from_sym_separated($fs, '|').unwrap_err())
+            };
+        }
+
+        test_mode_sym_separated!("rwx|rwx|rwx", "777");
+        test_mode_sym_separated!("rwx|r-x|r-x", "755");
+        test_mode_sym_separated!("rw-|r--|---", "640");
+        test_mode_sym_separated!("---|---|---", "000");
+
+        test_mode_sym_separated_e!("", ParseError::UnexpectedEoi { pos: 0 });
+        test_mode_sym_separated_e!("rwx", ParseError::UnexpectedEoi { pos: 3 });
+        test_mode_sym_separated_e!("rwx|r-x|", ParseError::UnexpectedEoi { pos: 8 });
+        test_mode_sym_separated_e!(
+            "rwxr-xr-x",
+            ParseError::UnexpectedChar {
+                pos: 3,
+                c: 'r',
+                expected: Some(vec!['|'])
+            }
+        );
+        test_mode_sym_separated_e!(
+            "rw|r-x|r-x",
+            ParseError::UnexpectedChar {
+                pos: 2,
+                c: '|',
+                expected: Some(vec!['x', 's', 'S', '-'])
+            }
+        );
+        test_mode_sym_separated_e!(
+            "rwx|rz-|r-x",
+            ParseError::UnexpectedChar {
+                pos: 5,
+                c: 'z',
+                expected: Some(vec!['w', '-'])
+            }
+        );
+        test_mode_sym_separated_e!(
+            "rwx|r-x|r-x|",
+            ParseError::UnexpectedChar {
+                pos: 11,
+                c: '|',
+                expected: None
+            }
+        );
+
+        assert_eq!(
+            Mode::from_num("4755")?,
+            Mode::from_sym_separated("rws-r-x-r-x", '-')?
+        );
+        assert_eq!(
+            ParseError::UnexpectedChar {
+                pos: 3,
+                c: 'r',
+                expected: Some(vec!['-'])
+            },
+            Mode::from_sym_separated("rwxr-x-r-x", '-').unwrap_err()
+        );
+        for m in Mode::all() {
+            for sep in ['|', '-', 'r', 'x', 't', 'é'] {
+                assert_eq!(m, Mode::from_sym_separated(&m.as_sym_separated(sep), sep)?);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mode_parse() -> Result<(), Box<dyn std::error::Error>> {
+        macro_rules! test_mode_parse_e {
+            ($s:expr, $err:expr) => {
+                assert_eq!($err, Mode::parse($s).unwrap_err())
+            };
+        }
+
+        assert_eq!(
+            mode!(true, true, true, true, false, true, true, false, true),
+            Mode::parse("755")?
+        );
+        assert_eq!(
+            mode!(true, true, false, true, false, false, true, false, false),
+            Mode::parse("rw-r--r--")?
+        );
+
+        test_mode_parse_e!("7", ParseError::ShortNum { len: 1, perm: true });
+        test_mode_parse_e!("0", ParseError::ShortNum { len: 1, perm: true });
+        test_mode_parse_e!(
+            "8",
+            ParseError::ShortNum {
+                len: 1,
+                perm: false
+            }
+        );
+        test_mode_parse_e!(
+            "75",
+            ParseError::ShortNum {
+                len: 2,
+                perm: false
+            }
+        );
+        test_mode_parse_e!("rwx", ParseError::UnexpectedEoi { pos: 3 });
+        test_mode_parse_e!("", ParseError::UnexpectedEoi { pos: 0 });
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mode_swap() -> Result<(), Box<dyn std::error::Error>> {
+        for n in 0..0o1000 {
+            let mode = Mode::from_num(&format!("{:03o}", n))?;
+            assert_eq!(mode, mode.swap_read_write().swap_read_write());
+            assert_eq!(mode, mode.swap_read_execute().swap_read_execute());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_perm_rotate() {
+        use perm::*;
+
+        for perm in ALL.iter() {
+            let rotated = perm.rotate_bits();
+            assert_eq!(perm.count(), rotated.count());
+            assert_eq!(*perm, rotated.rotate_bits().rotate_bits());
+        }
+        assert_eq!(RWX, RWX.rotate_bits());
+        assert_eq!(R, X.rotate_bits());
+    }
+
+    #[test]
+    fn test_count() {
+        use perm::*;
+
+        const USER: u32 = RWX.count();
+        const TOTAL: u32 = Mode::new(RWX, RWX, RWX).total_bits();
+        assert_eq!(3, USER);
+        assert_eq!(9, TOTAL);
+
+        const PARTIAL: u32 = Mode::new(RWX, RX, X).total_bits();
+        assert_eq!(6, PARTIAL);
+    }
+
+    #[test]
+    fn test_mode_neighbors() -> Result<(), Box<dyn std::error::Error>> {
+        for num in &["000", "644", "755", "777", "4755", "7777"] {
+            let mode = Mode::from_num(num)?;
+            let neighbors: Vec<_> = mode.neighbors().collect();
+
+            assert_eq!(9, neighbors.len());
+            for (i, neighbor) in neighbors.iter().enumerate() {
+                assert_eq!(1, mode.distance(neighbor));
+                assert!(mode.differs_by_at_most(neighbor, 1));
+                assert_eq!(mode.special_bits(), neighbor.special_bits());
+                assert!(neighbors[..i].iter().all(|n| n != neighbor));
+            }
+        }
+
+        let a = Mode::from_num("755")?;
+        for num in &["4755", "2755", "1755", "7755"] {
+            let b = Mode::from_num(num)?;
+            assert_eq!(0, a.distance(&b));
+            assert!(a.differs_by_at_most(&b, 0));
+            assert!(a.neighbors().all(|n| n != b));
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn test_perm_diff() {
         use DiffOp::*;
@@ -712,4 +5985,308 @@ mod test {
         test_perm_diff!(Minus, Plus, Same; true, false, false; false, true, false);
         test_perm_diff!(Same, Same, Same; false, false, true; false, false, true);
     }
+
+    #[test]
+    fn test_mode_diff_changes() -> Result<(), Box<dyn std::error::Error>> {
+        use Change::*;
+
+        assert_eq!(
+            vec![
+                Added(Class::User, 'x'),
+                Added(Class::Group, 'x'),
+                Added(Class::Other, 'x')
+            ],
+            Mode::from_num("644")?
+                .diff(&Mode::from_num("755")?)
+                .changes()
+        );
+        assert_eq!(
+            vec![
+                Removed(Class::User, 'r'),
+                Removed(Class::User, 'w'),
+                Added(Class::Group, 'w'),
+                Removed(Class::Other, 'x')
+            ],
+            Mode::from_num("741")?
+                .diff(&Mode::from_num("160")?)
+                .changes()
+        );
+        assert!(Mode::from_num("755")?
+            .diff(&Mode::from_num("755")?)
+            .changes()
+            .is_empty());
+        assert_eq!(
+            vec![
+                Removed(Class::User, 's'),
+                Added(Class::Group, 's'),
+                Added(Class::Other, 'x'),
+                Added(Class::Other, 't')
+            ],
+            Mode::from_num("4754")?
+                .diff(&Mode::from_num("3755")?)
+                .changes()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mode_chmod_changes() -> Result<(), Box<dyn std::error::Error>> {
+        assert_eq!(
+            "mode of 'a b' changed from 0644 (rw-r--r--) to 4755 (rwsr-xr-x)",
+            Mode::from_num("644")?.as_chmod_changes(&Mode::from_num("4755")?, "a b")
+        );
+        assert_eq!(
+            "mode of 'dir/f' changed from 0000 (---------) to 0007 (------rwx)",
+            Mode::from_num("000")?.as_chmod_changes(&Mode::from_num("007")?, "dir/f")
+        );
+        assert_eq!(
+            "mode of 'tmp' retained as 1777 (rwxrwxrwt)",
+            Mode::from_num("1777")?.as_chmod_changes(&Mode::from_num("1777")?, "tmp")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mode_apply() -> Result<(), Box<dyn std::error::Error>> {
+        for (a, b) in [
+            ("777", "644"),
+            ("000", "777"),
+            ("640", "705"),
+            ("4755", "4700"),
+            ("755", "755"),
+        ] {
+            let (a, b) = (Mode::parse(a)?, Mode::parse(b)?);
+            assert_eq!(b, a.apply(&a.diff(&b)));
+            assert_eq!(a, b.apply(&b.diff(&a)));
+        }
+
+        for a in 0..0o10000 {
+            for b in 0..0o10000 {
+                let (a, b) = (Mode::from_bits(a), Mode::from_bits(b));
+                assert_eq!(b, a.apply(&a.diff(&b)));
+            }
+        }
+
+        let diff = Mode::from_num("700")?.diff(&Mode::from_num("744")?);
+        assert_eq!(
+            Mode::from_num("1644")?,
+            Mode::from_num("1600")?.apply(&diff)
+        );
+        assert_eq!(
+            Mode::from_num("751")?,
+            Mode::from_num("751")?.apply(&Mode::from_num("751")?.diff(&Mode::from_num("751")?))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mode_add_assign() -> Result<(), Box<dyn std::error::Error>> {
+        let template = Mode::from_num("600")?.diff(&Mode::from_num("750")?);
+        for base in ["000", "644", "777", "4711"] {
+            let base = Mode::parse(base)?;
+            let mut m = base;
+            m += template;
+            assert_eq!(base.apply(&template), m);
+            assert_eq!(base + template, m);
+
+            let mut user = base.user;
+            user += template.user;
+            assert_eq!(m.user, user);
+            assert_eq!(m.user, base.user + template.user);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mode_umask() -> Result<(), Box<dyn std::error::Error>> {
+        assert_eq!(
+            Mode::from_num("022")?,
+            Mode::implied_umask(&Mode::from_num("777")?, &Mode::from_num("755")?)
+        );
+        assert_eq!(
+            Mode::from_num("026")?,
+            Mode::implied_umask(&Mode::from_num("666")?, &Mode::from_num("640")?)
+        );
+        assert_eq!(
+            Mode::from_num("000")?,
+            Mode::implied_umask(&Mode::from_num("644")?, &Mode::from_num("755")?)
+        );
+        assert_eq!(
+            Mode::from_num("4755")?,
+            Mode::from_num("4777")?.apply_umask(&Mode::from_num("1022")?)
+        );
+
+        assert_eq!(
+            Mode::from_num("750")?,
+            Mode::with_umask(Mode::from_num("777")?, Mode::from_num("027")?)
+        );
+        assert_eq!(Mode::from_num("600")?, Mode::from_umask_num("077", false)?);
+        assert_eq!(Mode::from_num("000")?, Mode::from_umask_num("777", true)?);
+        assert_eq!(Mode::from_num("777")?, Mode::from_umask_num("000", true)?);
+        assert_eq!(
+            ParseError::UnexpectedEoi { pos: 2 },
+            Mode::from_umask_num("02", true).unwrap_err()
+        );
+
+        for base in [0o777, 0o666] {
+            let base = Mode::from_bits(base);
+            for target in 0..0o1000 {
+                let target = Mode::from_bits(target);
+                let umask = Mode::implied_umask(&base, &target);
+                assert_eq!(
+                    Mode::from_bits(base.bits() & target.bits()),
+                    base.apply_umask(&umask)
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mode_grid() -> Result<(), Box<dyn std::error::Error>> {
+        assert_eq!(
+            "      r w x\n\
+             user  r w x\n\
+             group r   x\n\
+             other     x",
+            Mode::from_num("751")?.as_grid()
+        );
+        assert_eq!(
+            "      r w x\n\
+             user       \n\
+             group     S\n\
+             other r w t",
+            Mode::from_num("3007")?.as_grid()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mode_diff_split() -> Result<(), Box<dyn std::error::Error>> {
+        let (adds, removes) = Mode::from_num("644")?.diff(&Mode::from_num("755")?).split();
+        assert_eq!(Mode::from_num("111")?, adds);
+        assert_eq!(Mode::from_num("000")?, removes);
+
+        for a in 0..0o1000 {
+            for b in 0..0o1000 {
+                let (adds, removes) = Mode::from_bits(a).diff(&Mode::from_bits(b)).split();
+                assert_eq!(0, adds.bits() & removes.bits());
+                assert_eq!(b, (a | adds.bits()) & !removes.bits());
+                assert_eq!(b, (a & !removes.bits()) | adds.bits());
+            }
+        }
+
+        let (adds, removes) = Mode::from_num("4755")?
+            .diff(&Mode::from_num("3755")?)
+            .split();
+        assert_eq!(Mode::from_num("3000")?, adds);
+        assert_eq!(Mode::from_num("4000")?, removes);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_perm_diff_add_remove() -> Result<(), Box<dyn std::error::Error>> {
+        let (add, remove) = Perm::from_num("7")?
+            .diff(&Perm::from_num("6")?)
+            .to_add_remove();
+        assert_eq!("---", add.as_sym_full());
+        assert_eq!("--x", remove.as_sym_full());
+
+        for a in 0..0o10 {
+            for b in 0..0o10 {
+                let diff = Perm::from_bits(a).diff(&Perm::from_bits(b));
+                let (add, remove) = diff.to_add_remove();
+                assert_eq!(diff.split(), (add, remove));
+                assert_eq!(Perm::from_bits(b), (Perm::from_bits(a) | add) & !remove);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_diff_serde() -> Result<(), Box<dyn std::error::Error>> {
+        let diff = Mode::from_num("777")?.diff(&Mode::from_num("746")?);
+        let json = serde_json::to_string(&diff)?;
+
+        assert_eq!(
+            concat!(
+                r#"{"user":{"read":"same","write":"same","execute":"same"},"#,
+                r#""group":{"read":"same","write":"minus","execute":"minus"},"#,
+                r#""other":{"read":"same","write":"same","execute":"minus"},"#,
+                r#""setuid":"same","setgid":"same","sticky":"same"}"#
+            ),
+            json
+        );
+        assert_eq!(diff, serde_json::from_str(&json)?);
+
+        let op: DiffOp = serde_json::from_str(r#""plus""#)?;
+        assert_eq!(DiffOp::Plus, op);
+        assert!(serde_json::from_str::<DiffOp>(r#""Plus""#).is_err());
+
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_mode_serde() -> Result<(), Box<dyn std::error::Error>> {
+        let mode = Mode::from_num("755")?;
+        assert_eq!(r#""755""#, serde_json::to_string(&mode)?);
+        assert_eq!(mode, serde_json::from_str(r#""755""#)?);
+        assert_eq!(mode, serde_json::from_str(r#""rwxr-xr-x""#)?);
+
+        let mode = Mode::from_num("4755")?;
+        assert_eq!(r#""4755""#, serde_json::to_string(&mode)?);
+        assert_eq!(mode, serde_json::from_str(r#""rwsr-xr-x""#)?);
+
+        assert!(serde_json::from_str::<Mode>(r#""758""#).is_err());
+        assert!(serde_json::from_str::<Mode>("755").is_err());
+
+        let perm = perm::RX;
+        assert_eq!(r#""5""#, serde_json::to_string(&perm)?);
+        assert_eq!(perm, serde_json::from_str(r#""5""#)?);
+        assert_eq!(perm, serde_json::from_str(r#""r-x""#)?);
+        assert!(serde_json::from_str::<Perm>(r#""8""#).is_err());
+        assert!(serde_json::from_str::<Perm>(r#""rx""#).is_err());
+
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_structured_perm_serde() -> Result<(), Box<dyn std::error::Error>> {
+        let perm = StructuredPerm(perm::RX);
+        let json = serde_json::to_string(&perm)?;
+
+        assert_eq!(r#"{"read":true,"write":false,"execute":true}"#, json);
+        assert_eq!(perm, serde_json::from_str(&json)?);
+        assert!(serde_json::from_str::<StructuredPerm>(r#"{"read":true}"#).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_diff_op_ord() {
+        use DiffOp::*;
+
+        let mut ops = vec![Plus, Same, Minus, Same, Plus];
+        ops.sort();
+        assert_eq!(vec![Minus, Same, Same, Plus, Plus], ops);
+
+        assert_eq!(
+            vec![true, false, true],
+            [Minus, Same, Plus]
+                .iter()
+                .map(DiffOp::is_change)
+                .collect::<Vec<_>>()
+        );
+    }
 }
@@ -0,0 +1,758 @@
+//! Parsing of chmod-style symbolic expressions (e.g. `u=rwx,g=rx,o=` or `u+x,go-w`).
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{iter::Peekable, str::Chars};
+
+use crate::{perm::EMPTY, DiffOp, Mode, ModeDiff, ParseError, Perm, PermDiff};
+
+const WHO: [char; 4] = ['u', 'g', 'o', 'a'];
+const PERMS: [char; 3] = ['r', 'w', 'x'];
+const SPECIAL: [char; 2] = ['s', 't'];
+
+/// Operation of an action in a clause.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    /// Replace the permissions of a class (`=`).
+    Set,
+    /// Grant permissions to a class (`+`).
+    Add,
+    /// Revoke permissions from a class (`-`).
+    Remove,
+}
+
+impl Op {
+    #[inline]
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            '=' => Some(Self::Set),
+            '+' => Some(Self::Add),
+            '-' => Some(Self::Remove),
+            _ => None,
+        }
+    }
+
+    /// Apply the operation with the given permissions to a [`Perm`].
+    #[inline]
+    fn apply(&self, current: &Perm, perm: &Perm) -> Perm {
+        match self {
+            Self::Set => *perm,
+            Self::Add => current | perm,
+            Self::Remove => current & !perm,
+        }
+    }
+
+    /// Get the [`DiffOp`] recorded by the operation.
+    #[inline]
+    fn diff_op(&self) -> DiffOp {
+        match self {
+            Self::Add => DiffOp::Plus,
+            Self::Remove => DiffOp::Minus,
+            Self::Set => unreachable!("assignments are not parsed as diffs"),
+        }
+    }
+
+    /// Record the operation with the given permissions in a [`PermDiff`]. Permissions that are
+    /// not given are left unchanged.
+    #[inline]
+    fn record(&self, diff: &mut PermDiff, perm: &Perm) {
+        let op = self.diff_op();
+        for (set, slot) in [
+            (perm.read, &mut diff.read),
+            (perm.write, &mut diff.write),
+            (perm.execute, &mut diff.execute),
+        ] {
+            if set {
+                *slot = op;
+            }
+        }
+    }
+}
+
+/// Special bit letters of an action, which are only parsed in diffs.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct Special {
+    /// Whether `s` was given, which affects the *setuid* and *setgid* bits.
+    setid: bool,
+    /// Whether `t` was given, which affects the *sticky* bit.
+    sticky: bool,
+}
+
+/// Single clause of a symbolic expression (e.g. `go=rx`).
+#[derive(Debug, PartialEq)]
+struct Clause {
+    user: bool,
+    group: bool,
+    other: bool,
+    actions: Vec<(Op, Perm, Special)>,
+}
+
+impl Clause {
+    /// Apply the clause to a [`Mode`].
+    #[inline]
+    fn apply(&self, mode: &mut Mode) {
+        for (op, perm, _) in &self.actions {
+            if self.user {
+                mode.user = op.apply(&mode.user, perm);
+            }
+            if self.group {
+                mode.group = op.apply(&mode.group, perm);
+            }
+            if self.other {
+                mode.other = op.apply(&mode.other, perm);
+            }
+        }
+    }
+
+    /// Record the clause in a [`ModeDiff`]. The `s` letter affects the *setuid* bit for the user
+    /// class and the *setgid* bit for the group class, and the `t` letter affects the *sticky* bit
+    /// for the other class.
+    #[inline]
+    fn record(&self, diff: &mut ModeDiff) {
+        for (op, perm, special) in &self.actions {
+            if self.user {
+                op.record(&mut diff.user, perm);
+                if special.setid {
+                    diff.setuid = op.diff_op();
+                }
+            }
+            if self.group {
+                op.record(&mut diff.group, perm);
+                if special.setid {
+                    diff.setgid = op.diff_op();
+                }
+            }
+            if self.other {
+                op.record(&mut diff.other, perm);
+                if special.sticky {
+                    diff.sticky = op.diff_op();
+                }
+            }
+        }
+    }
+}
+
+/// Parse a comma-separated list of clauses of the form `[ugoa]*([op][rwx]*)+`, where `ops` are the
+/// permitted operator characters. If `special` is set, the `s` and `t` letters are also accepted
+/// after an operator. A clause without class letters applies to all classes.
+fn parse_clauses(expr: &str, ops: &[char], special: bool) -> Result<Vec<Clause>, ParseError> {
+    let mut chars = expr.chars().peekable();
+    let mut pos = 0;
+
+    let mut clauses = Vec::new();
+    loop {
+        let (user, group, other) = parse_who(&mut chars, &mut pos);
+        let actions = parse_actions(&mut chars, &mut pos, ops, true, special)?;
+
+        clauses.push(Clause {
+            user,
+            group,
+            other,
+            actions,
+        });
+
+        match chars.next() {
+            None => return Ok(clauses),
+            Some(',') => pos += 1,
+            Some(c) => {
+                return Err(ParseError::UnexpectedChar {
+                    pos,
+                    c,
+                    expected: Some(
+                        perm_letters(special)
+                            .iter()
+                            .chain(ops)
+                            .chain(&[','])
+                            .copied()
+                            .collect(),
+                    ),
+                })
+            }
+        }
+    }
+}
+
+/// Parse the actions of a clause of the form `([op][rwx]*)+`, where `ops` are the permitted
+/// operator characters. If `who` is set, class letters are included in the characters expected in
+/// place of the first operator, and if `special` is set, the `s` and `t` letters are accepted.
+fn parse_actions(
+    chars: &mut Peekable<Chars>,
+    pos: &mut usize,
+    ops: &[char],
+    who: bool,
+    special: bool,
+) -> Result<Vec<(Op, Perm, Special)>, ParseError> {
+    let mut actions = Vec::new();
+    loop {
+        let op = match chars.next() {
+            Some(c) if ops.contains(&c) => Op::from_char(c).unwrap(),
+            Some(c) => {
+                let expected = if who && actions.is_empty() {
+                    WHO.iter().chain(ops).copied().collect()
+                } else {
+                    ops.to_vec()
+                };
+                return Err(ParseError::UnexpectedChar {
+                    pos: *pos,
+                    c,
+                    expected: Some(expected),
+                });
+            }
+            None => return Err(ParseError::UnexpectedEoi { pos: *pos }),
+        };
+        *pos += 1;
+
+        let (perm, letters) = parse_perm(chars, pos, special);
+        actions.push((op, perm, letters));
+
+        match chars.peek() {
+            Some(c) if ops.contains(c) => continue,
+            _ => return Ok(actions),
+        }
+    }
+}
+
+/// Parse the class letters of a clause, returning flags for the user, group, and other classes.
+#[inline]
+fn parse_who(chars: &mut Peekable<Chars>, pos: &mut usize) -> (bool, bool, bool) {
+    let (mut user, mut group, mut other) = (false, false, false);
+    while let Some(c) = chars.peek() {
+        match c {
+            'u' => user = true,
+            'g' => group = true,
+            'o' => other = true,
+            'a' => {
+                user = true;
+                group = true;
+                other = true;
+            }
+            _ => break,
+        }
+        chars.next();
+        *pos += 1;
+    }
+
+    if user || group || other {
+        (user, group, other)
+    } else {
+        (true, true, true)
+    }
+}
+
+/// Parse the permission letters of an action, in any order, along with the `s` and `t` letters
+/// if `special` is set.
+#[inline]
+fn parse_perm(chars: &mut Peekable<Chars>, pos: &mut usize, special: bool) -> (Perm, Special) {
+    let mut perm = EMPTY;
+    let mut letters = Special::default();
+    while let Some(c) = chars.peek() {
+        match c {
+            'r' => perm.read = true,
+            'w' => perm.write = true,
+            'x' => perm.execute = true,
+            's' if special => letters.setid = true,
+            't' if special => letters.sticky = true,
+            _ => break,
+        }
+        chars.next();
+        *pos += 1;
+    }
+
+    (perm, letters)
+}
+
+/// Get the letters accepted after an operator, with the `s` and `t` letters if `special` is set.
+#[inline]
+fn perm_letters(special: bool) -> Vec<char> {
+    let special: &[char] = if special { &SPECIAL } else { &[] };
+    PERMS.iter().chain(special).copied().collect()
+}
+
+impl Mode {
+    /// Create a [`Mode`] from a comma-separated list of chmod-style assignments (e.g.
+    /// `u=rwx,g=rx,o=`), returning [`ParseError`] if the input is invalid.
+    ///
+    /// Each clause assigns permissions to the classes named by its letters (`u`, `g`, `o`, or `a`
+    /// for all), or to all classes if none are named. Permission letters may appear in any order.
+    /// Later clauses override earlier ones, and classes that are never assigned have no
+    /// permissions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::{Mode, ParseError};
+    ///
+    /// assert_eq!("750", Mode::from_assignment_list("u=rwx,g=rx,o=").unwrap().as_num());
+    /// assert_eq!("700", Mode::from_assignment_list("u=rwx").unwrap().as_num());
+    /// assert_eq!("744", Mode::from_assignment_list("a=r,u=xrw").unwrap().as_num());
+    ///
+    /// assert_eq!(
+    ///     ParseError::UnexpectedChar {
+    ///         pos: 1,
+    ///         c: '+',
+    ///         expected: Some(vec!['u', 'g', 'o', 'a', '='])
+    ///     },
+    ///     Mode::from_assignment_list("u+x").unwrap_err()
+    /// );
+    /// ```
+    pub fn from_assignment_list(list: &str) -> Result<Self, ParseError> {
+        let mut mode = Self::new(EMPTY, EMPTY, EMPTY);
+        for clause in parse_clauses(list, &['='], false)? {
+            clause.apply(&mut mode);
+        }
+
+        Ok(mode)
+    }
+
+    /// Apply a comma-separated list of chmod-style operations (e.g. `u+x,go-w`) to the [`Mode`],
+    /// returning [`ParseError`] if the input is invalid.
+    ///
+    /// Each clause applies to the classes named by its letters (`u`, `g`, `o`, or `a` for all),
+    /// or to all classes if none are named. `=` replaces the permissions of the classes, `+`
+    /// grants permissions, and `-` revokes them. Clauses are applied in order, and the special
+    /// bits are kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::{Mode, ParseError};
+    ///
+    /// let m = Mode::from_num("644").unwrap();
+    /// assert_eq!("744", m.apply_ops("u+x").unwrap().as_num());
+    /// assert_eq!("600", m.apply_ops("go-r").unwrap().as_num());
+    /// assert_eq!("755", m.apply_ops("+x,u+w").unwrap().as_num());
+    /// assert_eq!("751", m.apply_ops("a=rwx,g-w,o=x").unwrap().as_num());
+    ///
+    /// assert_eq!(
+    ///     ParseError::UnexpectedChar {
+    ///         pos: 4,
+    ///         c: 'z',
+    ///         expected: Some(vec!['r', 'w', 'x', '=', '+', '-', ','])
+    ///     },
+    ///     m.apply_ops("u+rwz").unwrap_err()
+    /// );
+    /// ```
+    pub fn apply_ops(&self, expr: &str) -> Result<Self, ParseError> {
+        let mut mode = *self;
+        for clause in parse_clauses(expr, &['=', '+', '-'], false)? {
+            clause.apply(&mut mode);
+        }
+
+        Ok(mode)
+    }
+
+    /// Apply a comma-separated list of chmod-style operations to the [`Mode`] (see
+    /// [`Mode::apply_ops`]), also returning whether the resulting [`Mode`] differs from this one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// let m = Mode::from_num("644").unwrap();
+    /// assert_eq!((m, false), m.apply_ops_checked("u+r").unwrap());
+    /// assert_eq!((Mode::from_num("744").unwrap(), true), m.apply_ops_checked("u+x").unwrap());
+    /// ```
+    #[inline]
+    pub fn apply_ops_checked(&self, expr: &str) -> Result<(Self, bool), ParseError> {
+        let mode = self.apply_ops(expr)?;
+        Ok((mode, mode != *self))
+    }
+
+    /// Return the representation of the [`Mode`] as a list of chmod-style assignments, the
+    /// inverse of [`Mode::from_assignment_list`].
+    ///
+    /// The compact form is used: every class is assigned, but only the letters of permissions
+    /// that are set are written (e.g. `u=rwx,g=rx,o=` rather than `u=rwx,g=r-x,o=---`), since
+    /// `-` is not valid in an assignment.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// let mode = Mode::from_num("750").unwrap();
+    /// assert_eq!("u=rwx,g=rx,o=", mode.to_assignment_list());
+    /// assert_eq!(mode, Mode::from_assignment_list(&mode.to_assignment_list()).unwrap());
+    /// ```
+    pub fn to_assignment_list(&self) -> String {
+        format!(
+            "u={},g={},o={}",
+            self.user.as_sym(),
+            self.group.as_sym(),
+            self.other.as_sym()
+        )
+    }
+}
+
+impl ModeDiff {
+    /// Get the chmod-style symbolic representation of the diff (e.g. `u-x,g-wx,o-wx`), the same
+    /// as its [`Display`](std::fmt::Display) form.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::Mode;
+    ///
+    /// let diff = Mode::from_num("755").unwrap().diff(&Mode::from_num("664").unwrap());
+    /// assert_eq!("u-x,g+w-x,o-x", diff.as_sym());
+    ///
+    /// let diff = Mode::from_num("4755").unwrap().diff(&Mode::from_num("3755").unwrap());
+    /// assert_eq!("u-s,g+s,o+t", diff.as_sym());
+    /// ```
+    #[inline]
+    pub fn as_sym(&self) -> String {
+        self.to_string()
+    }
+
+    /// Create a [`ModeDiff`] from a comma-separated list of chmod-style operations (e.g.
+    /// `u+x,go-w`), the inverse of [`ModeDiff::as_sym`], returning [`ParseError`] if the input is
+    /// invalid.
+    ///
+    /// Each clause applies to the classes named by its letters (`u`, `g`, `o`, or `a` for all),
+    /// or to all classes if none are named. Only `+` and `-` are accepted, and later operations
+    /// on a permission override earlier ones. The special bits are written as `s` (*setuid* for
+    /// the user class, *setgid* for the group class) and `t` (*sticky* for the other class). The
+    /// empty string is an empty diff.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::{Mode, ModeDiff, ParseError};
+    ///
+    /// let a = Mode::from_num("755").unwrap();
+    /// let b = Mode::from_num("664").unwrap();
+    ///
+    /// assert_eq!(a.diff(&b), ModeDiff::from_sym("u-x,g+w-x,o-x").unwrap());
+    /// assert_eq!(a.diff(&b), ModeDiff::from_sym("-x,g+w").unwrap());
+    /// assert_eq!(a.diff(&a), ModeDiff::from_sym("").unwrap());
+    ///
+    /// let c = Mode::from_num("6755").unwrap();
+    /// assert_eq!(a.diff(&c), ModeDiff::from_sym("ug+s").unwrap());
+    ///
+    /// assert_eq!(
+    ///     ParseError::UnexpectedChar {
+    ///         pos: 1,
+    ///         c: '=',
+    ///         expected: Some(vec!['u', 'g', 'o', 'a', '+', '-'])
+    ///     },
+    ///     ModeDiff::from_sym("u=x").unwrap_err()
+    /// );
+    /// ```
+    pub fn from_sym(sym: &str) -> Result<Self, ParseError> {
+        let same = EMPTY.diff(&EMPTY);
+        let mut diff = ModeDiff {
+            user: same,
+            group: same,
+            other: same,
+            setuid: DiffOp::Same,
+            setgid: DiffOp::Same,
+            sticky: DiffOp::Same,
+        };
+        if !sym.is_empty() {
+            for clause in parse_clauses(sym, &['+', '-'], true)? {
+                clause.record(&mut diff);
+            }
+        }
+
+        Ok(diff)
+    }
+}
+
+impl PermDiff {
+    /// Get the chmod-style symbolic representation of the diff (e.g. `+x-w`), the same as its
+    /// [`Display`](std::fmt::Display) form.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::perm::*;
+    ///
+    /// assert_eq!("+x-w", RW.diff(&RX).as_sym());
+    /// ```
+    #[inline]
+    pub fn as_sym(&self) -> String {
+        self.to_string()
+    }
+
+    /// Create a [`PermDiff`] from chmod-style operations without class letters (e.g. `+x-w`),
+    /// the inverse of [`PermDiff::as_sym`], returning [`ParseError`] if the input is invalid.
+    /// Later operations on a permission override earlier ones, and the empty string is an empty
+    /// diff.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cchmod::{perm::*, ParseError, PermDiff};
+    ///
+    /// assert_eq!(RW.diff(&RX), PermDiff::from_sym("+x-w").unwrap());
+    /// assert_eq!(RW.diff(&RW), PermDiff::from_sym("").unwrap());
+    ///
+    /// assert_eq!(
+    ///     ParseError::UnexpectedChar { pos: 0, c: 'u', expected: Some(vec!['+', '-']) },
+    ///     PermDiff::from_sym("u+x").unwrap_err()
+    /// );
+    /// ```
+    pub fn from_sym(sym: &str) -> Result<Self, ParseError> {
+        let mut diff = EMPTY.diff(&EMPTY);
+        if sym.is_empty() {
+            return Ok(diff);
+        }
+
+        let ops = ['+', '-'];
+        let mut chars = sym.chars().peekable();
+        let mut pos = 0;
+        for (op, perm, _) in parse_actions(&mut chars, &mut pos, &ops, false, false)? {
+            op.record(&mut diff, &perm);
+        }
+
+        match chars.next() {
+            None => Ok(diff),
+            Some(c) => Err(ParseError::UnexpectedChar {
+                pos,
+                c,
+                expected: Some(PERMS.iter().chain(&ops).copied().collect()),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::vec;
+
+    use super::*;
+    use crate::perm::*;
+
+    #[test]
+    fn test_parse_clauses() {
+        macro_rules! test_parse_clauses {
+            ($expr:expr; $($u:expr, $g:expr, $o:expr => [$($op:expr, $p:expr),*]);*) => {
+                assert_eq!(
+                    Ok(vec![$(Clause {
+                        user: $u,
+                        group: $g,
+                        other: $o,
+                        actions: vec![$(($op, $p, Special::default())),*],
+                    }),*]),
+                    parse_clauses($expr, &['='], false)
+                )
+            };
+        }
+
+        macro_rules! test_parse_clauses_e {
+            ($expr:expr, $err:expr) => {
+                assert_eq!(Err($err), parse_clauses($expr, &['='], false))
+            };
+        }
+
+        test_parse_clauses!("u=rwx"; true, false, false => [Op::Set, RWX]);
+        test_parse_clauses!("=x"; true, true, true => [Op::Set, X]);
+        test_parse_clauses!(
+            "go=wr=,a=";
+            false, true, true => [Op::Set, RW, Op::Set, EMPTY];
+            true, true, true => [Op::Set, EMPTY]
+        );
+
+        test_parse_clauses_e!("", ParseError::UnexpectedEoi { pos: 0 });
+        test_parse_clauses_e!("u", ParseError::UnexpectedEoi { pos: 1 });
+        test_parse_clauses_e!("u=r,", ParseError::UnexpectedEoi { pos: 4 });
+        test_parse_clauses_e!(
+            "u=r-",
+            ParseError::UnexpectedChar {
+                pos: 3,
+                c: '-',
+                expected: Some(vec!['r', 'w', 'x', '=', ','])
+            }
+        );
+        test_parse_clauses_e!(
+            "uz=r",
+            ParseError::UnexpectedChar {
+                pos: 1,
+                c: 'z',
+                expected: Some(vec!['u', 'g', 'o', 'a', '='])
+            }
+        );
+    }
+
+    #[test]
+    fn test_apply_ops() -> Result<(), ParseError> {
+        macro_rules! test_apply_ops {
+            ($base:expr, $expr:expr, $c:expr) => {
+                assert_eq!($c, Mode::parse($base)?.apply_ops($expr)?.as_num())
+            };
+        }
+
+        macro_rules! test_apply_ops_e {
+            ($expr:expr, $err:expr) => {
+                assert_eq!(Err($err), Mode::from_num("644").unwrap().apply_ops($expr))
+            };
+        }
+
+        test_apply_ops!("644", "u+x", "744");
+        test_apply_ops!("644", "+x", "755");
+        test_apply_ops!("777", "go-w", "755");
+        test_apply_ops!("000", "a=rwx", "777");
+        test_apply_ops!("777", "o=", "770");
+        test_apply_ops!("600", "g+r-w+x", "650");
+        test_apply_ops!("755", "u-x+x=r", "455");
+        test_apply_ops!("4755", "o-rx", "4750");
+        test_apply_ops!("755", "ug+w,o-x", "774");
+
+        test_apply_ops_e!("", ParseError::UnexpectedEoi { pos: 0 });
+        test_apply_ops_e!("u+x,", ParseError::UnexpectedEoi { pos: 4 });
+        test_apply_ops_e!(
+            "uq+x",
+            ParseError::UnexpectedChar {
+                pos: 1,
+                c: 'q',
+                expected: Some(vec!['u', 'g', 'o', 'a', '=', '+', '-'])
+            }
+        );
+        test_apply_ops_e!(
+            "u+x;g-w",
+            ParseError::UnexpectedChar {
+                pos: 3,
+                c: ';',
+                expected: Some(vec!['r', 'w', 'x', '=', '+', '-', ','])
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_ops_checked() -> Result<(), ParseError> {
+        macro_rules! test_apply_ops_checked {
+            ($base:expr, $expr:expr, $c:expr, $changed:expr) => {
+                assert_eq!(
+                    (Mode::parse($c)?, $changed),
+                    Mode::parse($base)?.apply_ops_checked($expr)?
+                )
+            };
+        }
+
+        test_apply_ops_checked!("644", "u+r", "644", false);
+        test_apply_ops_checked!("644", "go-wx", "644", false);
+        test_apply_ops_checked!("644", "a=r,u+w", "644", false);
+        test_apply_ops_checked!("644", "u+x", "744", true);
+        test_apply_ops_checked!("644", "u+x,u-x", "644", false);
+        test_apply_ops_checked!("4755", "=rx", "4555", true);
+
+        assert_eq!(
+            Err(ParseError::UnexpectedEoi { pos: 4 }),
+            Mode::from_num("644")?.apply_ops_checked("u+x,")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_diff_sym_round_trip() -> Result<(), ParseError> {
+        for a in 0..0o10000 {
+            for b in (0..0o10000).step_by(61) {
+                let diff = Mode::from_bits(a).diff(&Mode::from_bits(b));
+                assert_eq!(diff, ModeDiff::from_sym(&diff.as_sym())?);
+                assert_eq!(diff.user, PermDiff::from_sym(&diff.user.as_sym())?);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_diff_from_sym() -> Result<(), ParseError> {
+        use DiffOp::*;
+
+        macro_rules! perm_diff {
+            ($r:expr, $w:expr, $x:expr) => {
+                PermDiff {
+                    read: $r,
+                    write: $w,
+                    execute: $x,
+                }
+            };
+        }
+
+        assert_eq!(
+            ModeDiff {
+                user: perm_diff!(Same, Same, Plus),
+                group: perm_diff!(Same, Minus, Plus),
+                other: perm_diff!(Same, Minus, Plus),
+                setuid: Same,
+                setgid: Same,
+                sticky: Same,
+            },
+            ModeDiff::from_sym("a+x,go-w")?
+        );
+        assert_eq!(
+            ModeDiff {
+                user: perm_diff!(Plus, Same, Same),
+                group: perm_diff!(Same, Same, Same),
+                other: perm_diff!(Same, Same, Same),
+                setuid: Same,
+                setgid: Same,
+                sticky: Same,
+            },
+            ModeDiff::from_sym("u-r+r")?
+        );
+        assert_eq!(
+            Mode::from_num("6000")?.diff(&Mode::from_num("1000")?),
+            ModeDiff::from_sym("o+t,ug-s")?
+        );
+        assert_eq!(
+            Mode::from_num("000")?.diff(&Mode::from_num("7000")?),
+            ModeDiff::from_sym("+st")?
+        );
+        assert_eq!(
+            Mode::from_num("755")?.diff(&Mode::from_num("755")?),
+            ModeDiff::from_sym("u+t,o+s")?
+        );
+        assert_eq!(perm_diff!(Minus, Plus, Plus), PermDiff::from_sym("-r+wx")?);
+
+        assert_eq!(
+            Err(ParseError::UnexpectedEoi { pos: 1 }),
+            ModeDiff::from_sym("u")
+        );
+        assert_eq!(
+            Err(ParseError::UnexpectedChar {
+                pos: 3,
+                c: '=',
+                expected: Some(vec!['r', 'w', 'x', 's', 't', '+', '-', ','])
+            }),
+            ModeDiff::from_sym("u+x=")
+        );
+        assert_eq!(
+            Err(ParseError::UnexpectedChar {
+                pos: 2,
+                c: ',',
+                expected: Some(vec!['r', 'w', 'x', '+', '-'])
+            }),
+            PermDiff::from_sym("+x,-w")
+        );
+        assert_eq!(
+            Err(ParseError::UnexpectedChar {
+                pos: 0,
+                c: 'x',
+                expected: Some(vec!['+', '-'])
+            }),
+            PermDiff::from_sym("x")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_assignment_list_round_trip() {
+        for bits in 0..0o1000 {
+            let mode = Mode::from_bits(bits);
+            assert_eq!(
+                mode,
+                Mode::from_assignment_list(&mode.to_assignment_list()).unwrap()
+            );
+        }
+    }
+}
@@ -33,3 +33,16 @@ const_perm!(WX, _3, "-wx", false, true, true);
 const_perm!(W, _2, "-w-", false, true, false);
 const_perm!(X, _1, "--x", false, false, true);
 const_perm!(EMPTY, _0, "---", false, false, false);
+
+/// Every [`Perm`] value, in octal order (`ALL[n]` has the octal value `n`).
+///
+/// # Examples
+///
+/// ```
+/// use cchmod::perm::{self, ALL};
+///
+/// assert_eq!(perm::EMPTY, ALL[0]);
+/// assert_eq!(perm::RX, ALL[5]);
+/// assert_eq!(perm::RWX, ALL[7]);
+/// ```
+pub const ALL: [Perm; 8] = [EMPTY, X, W, WX, R, RX, RW, RWX];
@@ -0,0 +1,65 @@
+//! Utilities for testing code that extends [`Mode`], enabled by the `test-util` feature.
+
+use alloc::{format, string::String};
+
+use crate::Mode;
+
+/// Check that every [`Mode`] (see [`Mode::all`]) survives a round trip through its octal,
+/// symbolic, packed (see [`Mode::stable_id`]), and boolean CSV forms, returning a description of
+/// the first failure. The boolean CSV form does not include the special bits, so they are not
+/// expected to survive its round trip.
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(Ok(()), cchmod::test_util::verify_all_roundtrips());
+/// ```
+pub fn verify_all_roundtrips() -> Result<(), String> {
+    for mode in Mode::all() {
+        let check = |form: &str, expected: Mode, result: Result<Mode, String>| match result {
+            Ok(parsed) if parsed == expected => Ok(()),
+            Ok(parsed) => Err(format!(
+                "{} round trip of {:04o} produced {:04o}",
+                form,
+                mode.stable_id(),
+                parsed.stable_id()
+            )),
+            Err(err) => Err(format!(
+                "{} round trip of {:04o} failed: {}",
+                form,
+                mode.stable_id(),
+                err
+            )),
+        };
+
+        let num = mode.as_num();
+        check(
+            "octal",
+            mode,
+            Mode::from_num(&num).map_err(|err| err.render_with_input(&num)),
+        )?;
+        let sym = mode.as_sym();
+        check(
+            "symbolic",
+            mode,
+            Mode::from_sym(&sym).map_err(|err| err.render_with_input(&sym)),
+        )?;
+        check("packed", mode, Ok(Mode::from(u32::from(mode.stable_id()))))?;
+        let csv = mode.to_bool_csv();
+        check(
+            "boolean CSV",
+            mode.without_special(),
+            Mode::from_bool_csv(&csv).map_err(|err| err.render_with_input(&csv)),
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn test_verify_all_roundtrips() {
+        assert_eq!(Ok(()), super::verify_all_roundtrips());
+    }
+}
@@ -1,6 +1,8 @@
 //! Additional related convenience traits.
 
-use crate::{Mode, Perm};
+use alloc::string::String;
+
+use crate::{Class, Mode, Perm};
 
 /// Trait for conversion of a value into numerical form.
 pub trait AsNum {
@@ -12,6 +14,33 @@ pub trait AsSym {
     fn as_sym(&self) -> String;
 }
 
+/// Trait for processing the classes of a [`Mode`] with [`Mode::accept`].
+///
+/// # Examples
+///
+/// ```
+/// use cchmod::{traits::ModeVisitor, Class, Mode, Perm};
+///
+/// #[derive(Default)]
+/// struct Executable(Vec<Class>);
+///
+/// impl ModeVisitor for Executable {
+///     fn visit_class(&mut self, class: Class, perm: Perm) {
+///         if perm.execute {
+///             self.0.push(class);
+///         }
+///     }
+/// }
+///
+/// let mut v = Executable::default();
+/// Mode::from_num("751").unwrap().accept(&mut v);
+/// assert_eq!(vec![Class::User, Class::Group, Class::Other], v.0);
+/// ```
+pub trait ModeVisitor {
+    /// Process the permissions of a single class.
+    fn visit_class(&mut self, class: Class, perm: Perm);
+}
+
 impl AsNum for Mode {
     #[inline]
     fn as_num(&self) -> String {